@@ -1,9 +1,10 @@
 use bevy::prelude::*;
-use stttwmdtt::ActiveGame;
+use stttwmdtt::ActiveGames;
 use stttwmdtt_derive::WrapperEvent;
 
 use crate::ttt::{
-    GameActive, GameId, HoveredPosition, MouseExitedCell, MouseExitedGame, WrapperEvent,
+    GameActive, GameId, GameSystems, HoveredPosition, MouseExitedCell, MouseExitedGame,
+    WrapperEvent,
 };
 
 #[derive(Event, WrapperEvent)]
@@ -11,20 +12,13 @@ pub struct ActivateGame(GameId);
 #[derive(Event, WrapperEvent)]
 pub struct DeactivateGame(GameId);
 
-pub struct ActiveGameListenerPlugin {
-    inital_games: u64,
-}
-impl ActiveGameListenerPlugin {
-    pub fn new(inital_games: u64) -> Self {
-        Self { inital_games }
-    }
-}
+pub struct ActiveGameListenerPlugin;
 
 fn activate_game(
     mut activate: EventReader<ActivateGame>,
     mut q_games: Query<(&mut Visibility, &GameId), With<GameActive>>,
 ) {
-    if let Some(event) = activate.read().last() {
+    for event in activate.read() {
         for (mut visibility, id) in q_games.iter_mut() {
             if id == &event.0 {
                 *visibility = Visibility::Visible;
@@ -43,7 +37,7 @@ fn deactivate_game(
         for (mut visibility, id) in q_games.iter_mut() {
             if id == &event.0 {
                 if let Some(pos) = &pos.grid_pos {
-                    exited_cell.send(pos.clone().into());
+                    exited_cell.send((*pos).into());
                 }
                 if let Some(id) = &pos.game_id {
                     exited_game.send(id.clone().into());
@@ -56,23 +50,17 @@ fn deactivate_game(
 
 impl Plugin for ActiveGameListenerPlugin {
     fn build(&self, app: &mut App) {
-        let start_active = self.inital_games / 2;
-        app.init_resource::<ActiveGame>()
+        app.init_resource::<ActiveGames>()
             .init_resource::<HoveredPosition>()
             .add_event::<ActivateGame>()
             .add_event::<DeactivateGame>()
             .add_event::<MouseExitedCell>()
             .add_event::<MouseExitedGame>()
-            .add_systems(Update, (activate_game, deactivate_game).chain())
             .add_systems(
-                PostStartup,
-                move |mut active_game: ResMut<ActiveGame>,
-                      mut activate: EventWriter<ActivateGame>,
-                      mut deactivate: EventWriter<DeactivateGame>| {
-                    deactivate.send(GameId(active_game.0).into());
-                    active_game.0 = start_active;
-                    activate.send(GameId(active_game.0).into());
-                },
+                Update,
+                (activate_game, deactivate_game)
+                    .chain()
+                    .in_set(GameSystems::View),
             );
     }
 }
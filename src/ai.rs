@@ -0,0 +1,265 @@
+use std::{cmp::Reverse, fmt::Debug};
+
+use stttwmdtt_derive::Builder;
+
+use crate::{game_state::lines, Action, GameState, GridPosition, Player, Victory};
+
+///Picks the actions of a computer player.
+pub trait Ai: Debug + Send + Sync {
+    ///The next action of the current player, `None` once the match is over.
+    fn choose(&self, state: &GameState) -> Option<Action>;
+}
+
+impl GameState {
+    ///Every empty cell of the games the current player may play in, later time steps first.
+    ///
+    /// Moves into past time steps are included, they branch off a new timeline.
+    pub fn legal_moves(&self) -> Vec<GridPosition> {
+        let grid_origin = (self.n() as i16 - 1) / 2;
+        let cells = -grid_origin..self.n() as i16 - grid_origin;
+        let mut games = self.reachable_games();
+        games.sort_by_key(|game| Reverse(game.time));
+        let mut moves = vec![];
+        for game in games {
+            let Some(board) = self.board(game) else {
+                continue;
+            };
+            for x in cells.clone() {
+                for y in cells.clone() {
+                    if board.get(x, y).is_none() {
+                        moves.push(game.cell(x, y));
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    ///Plays `pos` and passes the turn if nothing else is left to play, returns the number of
+    /// actions taken.
+    pub(crate) fn play(&mut self, pos: GridPosition) -> Option<usize> {
+        self.apply_move(pos).ok()?;
+        if self.can_submit() && self.submit_turn().is_ok() {
+            return Some(2);
+        }
+        Some(1)
+    }
+
+    pub(crate) fn take_back(&mut self, actions: usize) {
+        for _ in 0..actions {
+            self.undo();
+        }
+    }
+}
+
+const WIN: i32 = 1_000_000;
+///Worth of a won game.
+const GAME: i32 = 100;
+///Worth of a line one mark short of winning in a game the player to move may play in.
+const THREAT: i32 = 30;
+
+///Minimax with alpha-beta pruning.
+///
+/// Searches ever deeper up to `depth` moves, a move that leaves the turn with the same player
+/// counts as well. Stops early after visiting `max_nodes` positions and keeps the best move of
+/// the last finished depth.
+/// Where a move sends the opponent is part of every searched move, and positions where the
+/// player to move is sent into a game they can win right away score higher.
+#[derive(Builder, Clone, Debug)]
+pub struct AlphaBeta {
+    depth: u32,
+    max_nodes: u64,
+}
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            max_nodes: 200_000,
+        }
+    }
+}
+impl AlphaBeta {
+    ///`None` once `nodes` runs over the budget.
+    fn negamax(
+        &self,
+        state: &mut GameState,
+        depth: u32,
+        mut alpha: i32,
+        beta: i32,
+        nodes: &mut u64,
+    ) -> Option<i32> {
+        *nodes += 1;
+        if *nodes > self.max_nodes {
+            return None;
+        }
+        let player = state.current_player();
+        if let Some(result) = state.result() {
+            return Some(match result.winner {
+                //Quicker wins and slower losses first.
+                Some(winner) if winner == player => WIN + depth as i32,
+                Some(_) => -WIN - depth as i32,
+                None => 0,
+            });
+        }
+        let moves = state.legal_moves();
+        if depth == 0 || moves.is_empty() {
+            return Some(evaluate(state, player));
+        }
+        let mut best = -WIN * 2;
+        for pos in moves {
+            let Some(actions) = state.play(pos) else {
+                continue;
+            };
+            let score = if state.current_player() == player {
+                self.negamax(state, depth - 1, alpha, beta, nodes)
+            } else {
+                self.negamax(state, depth - 1, -beta, -alpha, nodes)
+                    .map(|score| -score)
+            };
+            state.take_back(actions);
+            best = best.max(score?);
+            alpha = alpha.max(best);
+            if alpha >= beta {
+                break;
+            }
+        }
+        Some(best)
+    }
+}
+impl Ai for AlphaBeta {
+    fn choose(&self, state: &GameState) -> Option<Action> {
+        if state.is_over() {
+            return None;
+        }
+        if state.can_submit() {
+            return Some(Action::Submit);
+        }
+        let mut state = state.clone();
+        let player = state.current_player();
+        let moves = state.legal_moves();
+        let mut best = *moves.first()?;
+        let mut nodes = 0;
+        for depth in 1..=self.depth.max(1) {
+            let mut best_at_depth = None;
+            let mut alpha = -WIN * 2;
+            for pos in moves.iter() {
+                let Some(actions) = state.play(*pos) else {
+                    continue;
+                };
+                let score = if state.current_player() == player {
+                    self.negamax(&mut state, depth - 1, alpha, WIN * 2, &mut nodes)
+                } else {
+                    self.negamax(&mut state, depth - 1, -WIN * 2, -alpha, &mut nodes)
+                        .map(|score| -score)
+                };
+                state.take_back(actions);
+                let Some(score) = score else {
+                    return Some(Action::Move(best));
+                };
+                if score > alpha {
+                    alpha = score;
+                    best_at_depth = Some(*pos);
+                }
+            }
+            if let Some(pos) = best_at_depth {
+                best = pos;
+            }
+        }
+        Some(Action::Move(best))
+    }
+}
+
+///Won games and lines that can still be won, from the view of `player`.
+pub(crate) fn evaluate(state: &GameState, player: Player) -> i32 {
+    let n = state.n();
+    let k = state.k();
+    let grid_origin = (n as i64 - 1) / 2;
+    let active = state.active_games();
+    let sign = |owner: Player| if owner == player { 1 } else { -1 };
+    let mut score = 0;
+    for game in state.games() {
+        let Some(board) = state.board(game) else {
+            continue;
+        };
+        if let Some(winner) = board.winner() {
+            score += sign(winner) * GAME;
+            continue;
+        }
+        if board.is_closed() {
+            continue;
+        }
+        for line in lines(n as i64, n as i64, k) {
+            let marks: Vec<Player> = line
+                .iter()
+                .filter_map(|(x, y)| board.get((x - grid_origin) as i16, (y - grid_origin) as i16))
+                .collect();
+            let Some(owner) = marks.first().copied() else {
+                continue;
+            };
+            if marks.iter().any(|mark| *mark != owner) {
+                continue;
+            }
+            let count = marks.len() as i32;
+            score += sign(owner) * count * count;
+            if owner == player && count == k as i32 - 1 && active.contains(&game) {
+                score += THREAT;
+            }
+        }
+    }
+    if let Victory::InARow(length) = state.victory() {
+        score += meta_lines(state, player, length);
+    }
+    score
+}
+
+///Lines of won games on the latest time step of every timeline.
+fn meta_lines(state: &GameState, player: Player, length: u8) -> i32 {
+    let mut score = 0;
+    for timeline in 0..state.timeline_count() {
+        let time = state.end(timeline).unwrap_or_default();
+        let games: Vec<_> = state.games_at(time, timeline).collect();
+        let winner = |column: i64, row: i64| {
+            games
+                .iter()
+                .find(|game| game.column as i64 == column && game.row as i64 == row)
+                .and_then(|game| state.board(*game))
+                .and_then(|board| board.winner())
+        };
+        for line in lines(
+            state.games_per_row() as i64,
+            state.game_rows() as i64,
+            length,
+        ) {
+            let winners: Vec<Option<Player>> = line.iter().map(|(c, r)| winner(*c, *r)).collect();
+            let won: Vec<Player> = winners.iter().flatten().copied().collect();
+            let Some(owner) = won.first().copied() else {
+                continue;
+            };
+            if won.iter().all(|w| *w == owner) {
+                let count = won.len() as i32;
+                score += if owner == player { 1 } else { -1 } * count * count * GAME / 2;
+            }
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{routing_rule_by_name, BoardPosition, GameState, Rules, Victory};
+
+    #[test]
+    fn legal_moves_reach_into_the_past() {
+        let rules = Rules::default().victory(Victory::InARow(1));
+        let mut state = GameState::new(rules, routing_rule_by_name("torus").unwrap());
+        let game = BoardPosition::new(0, 0);
+        state.play(game.at(0, 0).cell(0, 0)).unwrap();
+        state.play(game.at(1, 0).cell(1, 1)).unwrap();
+        //X is to move on T2 and may branch off from T0, where X was to move as well.
+        let moves = state.legal_moves();
+        assert_eq!(moves.len(), 7 + 9);
+        assert!(moves[..7].iter().all(|pos| pos.time == 2));
+        assert!(moves[7..].iter().all(|pos| pos.time == 0));
+        assert!(moves.contains(&game.at(0, 0).cell(0, 0)));
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+use stttwmdtt::{Action, Ai, BoardDrawn, BoardWon, Engines, GameState, MatchOver};
+
+use crate::ttt::{GameSystems, PlayMode};
+
+///A search running on another thread for the position it started from.
+pub struct Analysis {
+    task: Task<Option<Action>>,
+    ///[GameState::position] of the searched match.
+    position: String,
+}
+impl Analysis {
+    pub fn start(ai: Arc<dyn Ai>, state: &GameState) -> Self {
+        let position = state.clone();
+        Self {
+            task: AsyncComputeTaskPool::get().spawn(async move { ai.choose(&position) }),
+            position: state.position(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    ///The found action, `None` if the match moved on while searching.
+    pub fn finish(self, state: &GameState) -> Option<Action> {
+        let action = block_on(self.task);
+        action.filter(|_| self.position == state.position())
+    }
+}
+
+#[derive(Resource, Default)]
+struct Search(Option<Analysis>);
+
+fn start_search(state: Res<GameState>, engines: Res<Engines>, mut search: ResMut<Search>) {
+    if search.0.is_some() || state.is_over() {
+        return;
+    }
+    if let Some(ai) = engines.get(state.current_player()) {
+        search.0 = Some(Analysis::start(ai.clone(), &state));
+    }
+}
+
+///Plays the found action, unless the match moved on while searching.
+fn finish_search(
+    mut state: ResMut<GameState>,
+    mut search: ResMut<Search>,
+    mut board_won: EventWriter<BoardWon>,
+    mut board_drawn: EventWriter<BoardDrawn>,
+    mut match_over: EventWriter<MatchOver>,
+) {
+    if !matches!(&search.0, Some(analysis) if analysis.is_finished()) {
+        return;
+    }
+    let Some(analysis) = search.0.take() else {
+        return;
+    };
+    match analysis.finish(&state) {
+        Some(Action::Move(pos)) => {
+            if let Ok(outcome) = state.apply_move(pos) {
+                info!("{}: {}", outcome.player, Action::Move(outcome.position));
+                if let Some(won) = outcome.board_won {
+                    board_won.send(won);
+                }
+                if let Some(drawn) = outcome.board_drawn {
+                    board_drawn.send(drawn);
+                }
+                if let Some(over) = outcome.match_over {
+                    match_over.send(over);
+                }
+            }
+        }
+        Some(Action::Submit) => {
+            if let Ok(player) = state.submit_turn() {
+                info!("{}: {}, {} to move", player.other(), Action::Submit, player);
+            }
+        }
+        None => {}
+    }
+}
+
+///Lets the computer play the seats it was given, searching off the main thread.
+pub struct AiPlayerPlugin {
+    engines: Engines,
+}
+impl AiPlayerPlugin {
+    pub fn new(engines: Engines) -> Self {
+        Self { engines }
+    }
+}
+impl Plugin for AiPlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.engines.clone())
+            .init_resource::<Search>()
+            .add_systems(
+                Update,
+                (finish_search, start_search)
+                    .chain()
+                    .run_if(in_state(PlayMode::Play))
+                    .in_set(GameSystems::Input),
+            );
+    }
+}
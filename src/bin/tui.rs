@@ -0,0 +1,378 @@
+//! Plays stttwmdtt in the terminal.
+//!
+//! Arrow keys move the cursor over the boards, `[` and `]` walk through the time steps of a
+//! timeline and tab switches timelines. Without a terminal, stdin is played like in
+//! [headless](stttwmdtt::headless) mode instead. Options and the config file are the ones of the
+//! window, without the window options.
+
+use std::{
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+};
+
+use stttwmdtt::{
+    cli::{fail, usage},
+    headless, Action, Args, ArgsError, BoardPosition, Engines, Frontend, GameState, Player,
+};
+
+const KEYS_HELP: &str = "\
+Keys:
+  arrows or hjkl  move the cursor      [ ]  earlier or later time step
+  tab             next timeline        enter or space  play the cell
+  s               pass the turn        u r  undo or redo
+  q or ctrl+c     quit";
+
+const KEYS: &str =
+    "arrows move, [ ] time, tab timeline, enter plays, s submits, u/r undo/redo, q quits";
+///Pass the turn once every timeline on the present got a move.
+const AUTO_SUBMIT: bool = true;
+
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Tab,
+    Char(char),
+}
+
+///Puts the terminal into unbuffered mode without echo until dropped.
+///
+/// Ctrl+C arrives as a key instead of killing the process, so the terminal is always restored.
+struct RawMode;
+impl RawMode {
+    fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        stty(&["-icanon", "-echo", "-isig", "min", "1"]).then_some(RawMode)
+    }
+}
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&["icanon", "echo", "isig"]);
+    }
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn read_key(bytes: &mut impl Iterator<Item = u8>) -> Option<Key> {
+    Some(match bytes.next()? {
+        b'\r' | b'\n' | b' ' => Key::Enter,
+        b'\t' => Key::Tab,
+        //Ctrl+C
+        0x03 => Key::Char('q'),
+        0x1b => match (bytes.next()?, bytes.next()?) {
+            (b'[', b'A') => Key::Up,
+            (b'[', b'B') => Key::Down,
+            (b'[', b'C') => Key::Right,
+            (b'[', b'D') => Key::Left,
+            _ => Key::Char('\0'),
+        },
+        b'k' => Key::Up,
+        b'j' => Key::Down,
+        b'l' => Key::Right,
+        b'h' => Key::Left,
+        byte => Key::Char(byte as char),
+    })
+}
+
+///The cell under the cursor, counted over all games of a time step from the bottom left.
+struct Cursor {
+    timeline: u32,
+    time: u32,
+    x: u32,
+    y: u32,
+}
+impl Cursor {
+    fn cell(&self, state: &GameState) -> stttwmdtt::GridPosition {
+        let n = state.n() as u32;
+        let grid_origin = (n as i16 - 1) / 2;
+        BoardPosition::new(self.x / n, self.y / n)
+            .at(self.time, self.timeline)
+            .cell(
+                (self.x % n) as i16 - grid_origin,
+                (self.y % n) as i16 - grid_origin,
+            )
+    }
+
+    ///Onto the center of the first game the current player may play in, unless the cursor is
+    /// already on one.
+    fn follow(&mut self, state: &GameState) {
+        if state.is_reachable(self.cell(state).board()) {
+            return;
+        }
+        if let Some(game) = state.active_games().first() {
+            let n = state.n() as u32;
+            self.timeline = game.timeline;
+            self.time = game.time;
+            self.x = game.column * n + n / 2;
+            self.y = game.row * n + n / 2;
+        }
+    }
+
+    fn step(&mut self, state: &GameState, key: &Key) {
+        let n = state.n() as u32;
+        let (width, height) = (state.games_per_row() * n, state.game_rows() * n);
+        match key {
+            Key::Up => self.y = (self.y + 1).min(height - 1),
+            Key::Down => self.y = self.y.saturating_sub(1),
+            Key::Right => self.x = (self.x + 1).min(width - 1),
+            Key::Left => self.x = self.x.saturating_sub(1),
+            Key::Tab => {
+                self.timeline = (self.timeline + 1) % state.timeline_count();
+                self.time = state.end(self.timeline).unwrap_or_default();
+            }
+            Key::Char('[') => {
+                let start = state.start(self.timeline).unwrap_or_default();
+                self.time = self.time.saturating_sub(1).max(start);
+            }
+            Key::Char(']') => {
+                let end = state.end(self.timeline).unwrap_or_default();
+                self.time = (self.time + 1).min(end);
+            }
+            _ => {}
+        }
+    }
+}
+
+///Applies a command, returns what happened or why it did not.
+fn apply(state: &mut GameState, action: Result<Action, String>) -> Result<String, String> {
+    let player = state.current_player();
+    let done = match action? {
+        Action::Move(pos) => state
+            .apply_move(pos)
+            .map(|outcome| Action::Move(outcome.position))
+            .map_err(|error| error.to_string())?,
+        Action::Submit => state
+            .submit_turn()
+            .map(|_| Action::Submit)
+            .map_err(|error| error.to_string())?,
+    };
+    if AUTO_SUBMIT && state.can_submit() {
+        let _ = state.submit_turn();
+    }
+    Ok(format!("{}: {}", player, done))
+}
+
+///Computer moves are taken back together with the human move before them.
+fn undo(state: &mut GameState, engines: &Engines) -> Result<String, String> {
+    let human_plays = engines.x.is_none() || engines.o.is_none();
+    state
+        .undo_turn(AUTO_SUBMIT, |player| {
+            human_plays && engines.get(player).is_some()
+        })
+        .map(|action| format!("undid {}", action))
+        .ok_or("nothing to undo".to_string())
+}
+
+fn redo(state: &mut GameState) -> Result<String, String> {
+    let redone = match state.redo() {
+        Some(Ok(action)) => action,
+        Some(Err(error)) => return Err(error.to_string()),
+        None => return Err("nothing to redo".to_string()),
+    };
+    if AUTO_SUBMIT && state.undone_actions().next() == Some(Action::Submit) {
+        let _ = state.redo();
+    }
+    Ok(format!("redid {}", redone))
+}
+
+///Lets the computer seats move until a human is to play, returns what the last one did.
+fn play_engines(state: &mut GameState, engines: &Engines) -> Option<Result<String, String>> {
+    let mut played = None;
+    while !state.is_over() {
+        let Some(ai) = engines.get(state.current_player()) else {
+            break;
+        };
+        let Some(action) = ai.choose(state) else {
+            break;
+        };
+        let result = apply(state, Ok(action));
+        let failed = result.is_err();
+        played = Some(result);
+        if failed {
+            break;
+        }
+    }
+    played
+}
+
+///The message of a human action followed by the answer of the computer.
+fn answer(result: Result<String, String>, played: Option<Result<String, String>>) -> String {
+    let message = result.unwrap_or_else(|error| error);
+    match played {
+        Some(played) => format!("{}, {}", message, played.unwrap_or_else(|error| error)),
+        None => message,
+    }
+}
+
+///Border of a game: `#` for games to play in, the winner or `~` for closed games.
+fn border(state: &GameState, game: BoardPosition) -> Option<char> {
+    let board = state.board(game)?;
+    if state.is_reachable(game) {
+        Some('#')
+    } else if let Some(winner) = board.winner() {
+        Some(if winner == Player::X { 'X' } else { 'O' })
+    } else if board.is_closed() {
+        Some('~')
+    } else {
+        None
+    }
+}
+
+fn render_time_step(state: &GameState, cursor: &Cursor, time: u32, timeline: u32) -> String {
+    let n = state.n() as i16;
+    let grid_origin = (n - 1) / 2;
+    let under_cursor = cursor.cell(state);
+    let mut text = match state.parent(timeline) {
+        Some((parent, at)) => format!("L{} T{}, branched off L{}T{}\n", timeline, time, parent, at),
+        None => format!("L{} T{}\n", timeline, time),
+    };
+    for row in (0..state.game_rows()).rev() {
+        let games: Vec<BoardPosition> = (0..state.games_per_row())
+            .map(|column| BoardPosition::new(column, row).at(time, timeline))
+            .collect();
+        let edge = |game: &BoardPosition| {
+            let line = match border(state, *game) {
+                Some(c) => c.to_string().repeat(3 * n as usize),
+                None => "-".repeat(3 * n as usize),
+            };
+            let corner = border(state, *game).unwrap_or('+');
+            format!("{}{}{}", corner, line, corner)
+        };
+        let edges: Vec<String> = games.iter().map(edge).collect();
+        text += &edges.join(" ");
+        text += "\n";
+        for y in (0..n).rev() {
+            let lines: Vec<String> = games
+                .iter()
+                .map(|game| {
+                    let side = border(state, *game).unwrap_or('|');
+                    let cells: String = (0..n)
+                        .map(|x| {
+                            let cell = game.cell(x - grid_origin, y - grid_origin);
+                            let mark = match state.board(*game).and_then(|b| b.get(cell.x, cell.y))
+                            {
+                                Some(player) => player.to_string(),
+                                None => ".".to_string(),
+                            };
+                            if cell == under_cursor {
+                                format!("[{}]", mark)
+                            } else {
+                                format!(" {} ", mark)
+                            }
+                        })
+                        .collect();
+                    format!("{}{}{}", side, cells, side)
+                })
+                .collect();
+            text += &lines.join(" ");
+            text += "\n";
+        }
+        text += &edges.join(" ");
+        text += "\n";
+    }
+    text
+}
+
+///Every timeline at its latest time step, the one under the cursor at the cursor's time step.
+fn render(state: &GameState, cursor: &Cursor, message: &str) -> String {
+    let mut text = String::new();
+    for timeline in 0..state.timeline_count() {
+        let time = if timeline == cursor.timeline {
+            cursor.time
+        } else {
+            state.end(timeline).unwrap_or_default()
+        };
+        text += &render_time_step(state, cursor, time, timeline);
+        text += "\n";
+    }
+    match state.result() {
+        Some(result) => match result.winner {
+            Some(winner) => text += &format!("{} wins!\n", winner),
+            None => text += "Draw!\n",
+        },
+        None => {
+            let obligations: Vec<String> = state
+                .obligations()
+                .iter()
+                .map(|game| game.to_string())
+                .collect();
+            text += &format!(
+                "{} to move, present T{}, still to play in: {}\n",
+                state.current_player(),
+                state.present(),
+                obligations.join(" ")
+            );
+        }
+    }
+    text += &format!("cursor {}\n{}\n", cursor.cell(state), message);
+    text
+}
+
+fn play_keys(mut state: GameState, engines: &Engines, _raw: RawMode) {
+    let mut cursor = Cursor {
+        timeline: 0,
+        time: 0,
+        x: 0,
+        y: 0,
+    };
+    let mut message = answer(Ok(KEYS.to_string()), play_engines(&mut state, engines));
+    cursor.follow(&state);
+    let mut bytes = io::stdin().lock().bytes().map_while(Result::ok);
+    loop {
+        print!("\x1b[2J\x1b[H{}", render(&state, &cursor, &message));
+        let _ = io::stdout().flush();
+        let Some(key) = read_key(&mut bytes) else {
+            break;
+        };
+        let result = match key {
+            Key::Char('q') => break,
+            Key::Enter => {
+                let cell = cursor.cell(&state);
+                apply(&mut state, Ok(Action::Move(cell)))
+            }
+            Key::Char('s') => apply(&mut state, Ok(Action::Submit)),
+            Key::Char('u') => undo(&mut state, engines),
+            Key::Char('r') => redo(&mut state),
+            key => {
+                cursor.step(&state, &key);
+                continue;
+            }
+        };
+        message = answer(result, play_engines(&mut state, engines));
+        cursor.time = cursor
+            .time
+            .min(state.end(cursor.timeline).unwrap_or_default());
+        cursor.follow(&state);
+    }
+}
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1), Frontend::Terminal) {
+        Ok(args) => args,
+        Err(help @ ArgsError::Help(_)) => {
+            println!("{}\n\n{}", help, KEYS_HELP);
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, usage(Frontend::Terminal));
+            std::process::exit(2);
+        }
+    };
+    let config = args.load_config().unwrap_or_else(|error| fail(error));
+    let state = args.game_state(&config).unwrap_or_else(|error| fail(error));
+    let engines = args.engines();
+    match RawMode::enable() {
+        Some(raw) => play_keys(state, &engines, raw),
+        None => headless::run(state, &engines, AUTO_SUBMIT),
+    }
+}
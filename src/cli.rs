@@ -0,0 +1,412 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use crate::{
+    config::CONFIG_FILE, key_value, routing_rule_by_name, Ai, AlphaBeta, Config, ConfigError,
+    Engines, GameState, LoadError, Mcts, Player, PlayerKind, PositionError, Seats,
+};
+
+///The binary reading the arguments, the terminal has no window to size or draw into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Frontend {
+    Window,
+    Terminal,
+}
+
+const OPTIONS: &str = "\
+Options:
+  --config <FILE>          Layout, colours and rules [default: sttt.conf]
+  --grid <COLUMNS>x<ROWS>  Number of games per row and rows of games
+  -n <N>                   Cells per side of a game
+  --routing <RULE>         torus, clamped, classic or klein_bottle
+  -x <PLAYER>              Who plays X: human, alpha_beta or mcts [default: human]
+  -o <PLAYER>              Who plays O: human, alpha_beta or mcts [default: human]
+  --depth <MOVES>          How far alpha_beta looks ahead [default: 4]
+  --iterations <N>         Random matches mcts plays per move [default: 2000]
+  --think-time <SECONDS>   Stops mcts earlier, its moves are no longer reproducible
+  --exploration <C>        How much mcts favours rarely tried moves [default: 1.41]
+  --seed <N>               Seed of the random matches of mcts [default: 0]
+  --load <FILE>            Continue a saved match, its rules replace the configured ones
+  --position <POSITION>    Start from a position string instead of empty boards
+";
+const WINDOW_OPTIONS: &str =
+    "  --svg <FILE>             Draw the starting position into an SVG image and quit
+  --window <WIDTH>x<HEIGHT>
+                           Size of the window in pixels
+  --headless               Play without a window, one action per line on stdin
+";
+const HELP: &str = "  -h, --help               Print this help";
+
+///The options `frontend` understands.
+pub fn usage(frontend: Frontend) -> String {
+    match frontend {
+        Frontend::Window => format!(
+            "Usage: stttwmdtt [OPTIONS]\n\n{}{}{}",
+            OPTIONS, WINDOW_OPTIONS, HELP
+        ),
+        Frontend::Terminal => format!("Usage: tui [OPTIONS]\n\n{}{}", OPTIONS, HELP),
+    }
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    ///`--help` was given, displays the usage.
+    Help(Frontend),
+    Unknown(String),
+    MissingValue(&'static str),
+    InvalidValue {
+        flag: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    Conflict(&'static str, &'static str),
+}
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgsError::Help(frontend) => write!(f, "{}", usage(*frontend)),
+            ArgsError::Unknown(arg) => write!(f, "unknown argument `{}`", arg),
+            ArgsError::MissingValue(flag) => write!(f, "`{}` needs a value", flag),
+            ArgsError::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value `{}` for `{}`, expected {}",
+                value, flag, expected
+            ),
+            ArgsError::Conflict(first, second) => {
+                write!(f, "`{}` and `{}` can not be combined", first, second)
+            }
+        }
+    }
+}
+impl std::error::Error for ArgsError {}
+
+///Why the match given by the [Args] could not be set up.
+#[derive(Debug)]
+pub enum StartError {
+    Config { path: PathBuf, error: ConfigError },
+    Load { path: PathBuf, error: LoadError },
+    Position(PositionError),
+}
+impl Display for StartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartError::Config { path, error } => {
+                write!(f, "Invalid config {}: {}", path.display(), error)
+            }
+            StartError::Load { path, error } => {
+                write!(f, "Could not load {}: {}", path.display(), error)
+            }
+            StartError::Position(error) => write!(f, "Invalid position: {}", error),
+        }
+    }
+}
+impl std::error::Error for StartError {}
+
+///Prints `message` and exits with status 1.
+pub fn fail(message: impl Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+///How the binary was launched, see [usage].
+#[derive(Debug)]
+pub struct Args {
+    pub config: PathBuf,
+    ///Config keys set on the command line, their values are already checked.
+    pub overrides: Vec<(&'static str, String)>,
+    pub seats: Seats,
+    ///Search depth of [AlphaBeta] seats.
+    pub depth: Option<u32>,
+    ///Settings of [Mcts] seats.
+    pub mcts: Mcts,
+    pub load: Option<PathBuf>,
+    pub position: Option<String>,
+    ///Only draw the starting position into this image.
+    pub svg: Option<PathBuf>,
+    pub window: Option<(f32, f32)>,
+    pub headless: bool,
+}
+impl Args {
+    ///Reads the arguments without the name of the binary.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        frontend: Frontend,
+    ) -> Result<Self, ArgsError> {
+        let mut parsed = Self {
+            config: CONFIG_FILE.into(),
+            overrides: vec![],
+            seats: Seats::default(),
+            depth: None,
+            mcts: Mcts::default(),
+            load: None,
+            position: None,
+            svg: None,
+            window: None,
+            headless: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, mut inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = |flag| match inline.take() {
+                Some(value) => Ok(value),
+                None => args.next().ok_or(ArgsError::MissingValue(flag)),
+            };
+            match flag.as_str() {
+                "-h" | "--help" => return Err(ArgsError::Help(frontend)),
+                "--svg" | "--window" | "--headless" if frontend == Frontend::Terminal => {
+                    return Err(ArgsError::Unknown(arg))
+                }
+                "--headless" => parsed.headless = true,
+                "--config" => parsed.config = value("--config")?.into(),
+                "--grid" => {
+                    let value = value("--grid")?;
+                    let (columns, rows) = value
+                        .split_once('x')
+                        .ok_or_else(|| invalid("--grid", &value, "<COLUMNS>x<ROWS>"))?;
+                    let columns: u32 = positive("--grid", columns.to_string())?;
+                    let rows: u32 = positive("--grid", rows.to_string())?;
+                    parsed
+                        .overrides
+                        .push(("games_per_row", columns.to_string()));
+                    parsed.overrides.push(("game_rows", rows.to_string()));
+                }
+                "-n" => {
+                    let n: u8 = positive("-n", value("-n")?)?;
+                    parsed.overrides.push(("n", n.to_string()));
+                }
+                "--routing" => {
+                    let value = value("--routing")?;
+                    if routing_rule_by_name(&value).is_err() {
+                        return Err(invalid(
+                            "--routing",
+                            &value,
+                            "torus, clamped, classic or klein_bottle",
+                        ));
+                    }
+                    parsed.overrides.push(("routing_rule", value));
+                }
+                "-x" => parsed.seats.x = player_kind("-x", value("-x")?)?,
+                "-o" => parsed.seats.o = player_kind("-o", value("-o")?)?,
+                "--depth" => parsed.depth = Some(positive("--depth", value("--depth")?)?),
+                "--iterations" => {
+                    let iterations = positive("--iterations", value("--iterations")?)?;
+                    parsed.mcts = parsed.mcts.clone().iterations(iterations);
+                }
+                "--think-time" => {
+                    let value = value("--think-time")?;
+                    let limit = value
+                        .parse()
+                        .ok()
+                        .filter(|seconds| *seconds > 0.0)
+                        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(|| invalid("--think-time", &value, "seconds"))?;
+                    parsed.mcts = parsed.mcts.clone().time_limit(Some(limit));
+                }
+                "--exploration" => {
+                    let exploration = positive("--exploration", value("--exploration")?)?;
+                    parsed.mcts = parsed.mcts.clone().exploration(exploration);
+                }
+                "--seed" => {
+                    let value = value("--seed")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| invalid("--seed", &value, "a number"))?;
+                    parsed.mcts = parsed.mcts.clone().seed(seed);
+                }
+                "--load" => parsed.load = Some(value("--load")?.into()),
+                "--position" => parsed.position = Some(value("--position")?),
+                "--svg" => parsed.svg = Some(value("--svg")?.into()),
+                "--window" => {
+                    let value = value("--window")?;
+                    let size = value.split_once('x').and_then(|(width, height)| {
+                        Some((width.parse::<f32>().ok()?, height.parse::<f32>().ok()?))
+                    });
+                    parsed.window = Some(
+                        size.filter(|(width, height)| *width > 0.0 && *height > 0.0)
+                            .ok_or_else(|| invalid("--window", &value, "<WIDTH>x<HEIGHT>"))?,
+                    );
+                }
+                _ => return Err(ArgsError::Unknown(arg)),
+            }
+        }
+        if parsed.load.is_some() && parsed.position.is_some() {
+            return Err(ArgsError::Conflict("--load", "--position"));
+        }
+        if parsed.headless && parsed.svg.is_some() {
+            return Err(ArgsError::Conflict("--headless", "--svg"));
+        }
+        Ok(parsed)
+    }
+
+    ///The config file with the keys set on the command line.
+    pub fn load_config(&self) -> Result<Config, StartError> {
+        Config::load(&self.config, &self.overrides).map_err(|error| StartError::Config {
+            path: self.config.clone(),
+            error,
+        })
+    }
+
+    ///The loaded match, the match set up from the position or a new one with the rules of
+    /// `config`.
+    pub fn game_state(&self, config: &Config) -> Result<GameState, StartError> {
+        let (rules, routing_rule) = (config.rules.clone(), config.routing_rule.clone());
+        match (&self.load, &self.position) {
+            (Some(path), _) => GameState::load_file(path).map_err(|error| StartError::Load {
+                path: path.clone(),
+                error,
+            }),
+            (None, Some(position)) => GameState::from_position(rules, routing_rule, position)
+                .map_err(StartError::Position),
+            (None, None) => Ok(GameState::new(rules, routing_rule)),
+        }
+    }
+
+    ///The computer players of both seats.
+    pub fn engines(&self) -> Engines {
+        Engines {
+            x: self.ai(Player::X),
+            o: self.ai(Player::O),
+        }
+    }
+
+    ///The computer player in the seat of `player`, `None` for humans.
+    fn ai(&self, player: Player) -> Option<Arc<dyn Ai>> {
+        match self.seats.get(player) {
+            PlayerKind::Human => None,
+            PlayerKind::AlphaBeta => {
+                let mut search = AlphaBeta::default();
+                if let Some(depth) = self.depth {
+                    search = search.depth(depth);
+                }
+                Some(Arc::new(search))
+            }
+            PlayerKind::Mcts => Some(Arc::new(self.mcts.clone())),
+        }
+    }
+}
+
+fn player_kind(flag: &'static str, value: String) -> Result<PlayerKind, ArgsError> {
+    value
+        .parse()
+        .map_err(|_| invalid(flag, &value, "human, alpha_beta or mcts"))
+}
+
+fn positive<T: FromStr + PartialOrd + Default>(
+    flag: &'static str,
+    value: String,
+) -> Result<T, ArgsError> {
+    key_value::positive(&value).ok_or_else(|| invalid(flag, &value, "a positive number"))
+}
+
+fn invalid(flag: &'static str, value: &str, expected: &'static str) -> ArgsError {
+    ArgsError::InvalidValue {
+        flag,
+        value: value.to_string(),
+        expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], frontend: Frontend) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), frontend)
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args, Frontend::Window).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn help() {
+        for flag in ["-h", "--help"] {
+            assert!(matches!(
+                parse(&["-x", "mcts", flag], Frontend::Terminal),
+                Err(ArgsError::Help(Frontend::Terminal))
+            ));
+        }
+        let help = error(&["--help"]);
+        assert!(help.starts_with("Usage: stttwmdtt [OPTIONS]"));
+        assert!(help.contains("--headless"));
+        assert!(!usage(Frontend::Terminal).contains("--headless"));
+    }
+
+    #[test]
+    fn unknown_arguments() {
+        assert_eq!(error(&["--colour", "red"]), "unknown argument `--colour`");
+        assert!(matches!(
+            parse(&["--headless"], Frontend::Terminal),
+            Err(ArgsError::Unknown(arg)) if arg == "--headless"
+        ));
+    }
+
+    #[test]
+    fn missing_values() {
+        assert_eq!(error(&["--load"]), "`--load` needs a value");
+        assert!(matches!(
+            parse(&["-n", "4", "-x"], Frontend::Window),
+            Err(ArgsError::MissingValue("-x"))
+        ));
+    }
+
+    #[test]
+    fn seats_and_engines() {
+        let args = parse(
+            &["-x", "alpha_beta", "--depth=2", "-o", "mcts"],
+            Frontend::Window,
+        )
+        .unwrap();
+        assert_eq!(args.seats.x, PlayerKind::AlphaBeta);
+        assert_eq!(args.seats.o, PlayerKind::Mcts);
+        let engines = args.engines();
+        assert!(engines.x.is_some() && engines.o.is_some());
+        let args = parse(&["-o", "human"], Frontend::Window).unwrap();
+        assert!(args.engines().x.is_none() && args.engines().o.is_none());
+        assert_eq!(
+            error(&["-x", "random"]),
+            "invalid value `random` for `-x`, expected human, alpha_beta or mcts"
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let args = parse(&["--grid", "4x2", "-n", "5"], Frontend::Window).unwrap();
+        assert_eq!(
+            args.overrides,
+            [
+                ("games_per_row", "4".to_string()),
+                ("game_rows", "2".to_string()),
+                ("n", "5".to_string()),
+            ]
+        );
+        assert_eq!(
+            error(&["--grid", "3x"]),
+            "invalid value `` for `--grid`, expected a positive number"
+        );
+        assert_eq!(
+            error(&["--grid=0x3"]),
+            "invalid value `0` for `--grid`, expected a positive number"
+        );
+        assert_eq!(
+            error(&["--grid", "3"]),
+            "invalid value `3` for `--grid`, expected <COLUMNS>x<ROWS>"
+        );
+        assert_eq!(
+            error(&["-n", "0"]),
+            "invalid value `0` for `-n`, expected a positive number"
+        );
+        assert_eq!(
+            error(&["--routing", "sphere"]),
+            "invalid value `sphere` for `--routing`, expected torus, clamped, classic or klein_bottle"
+        );
+    }
+}
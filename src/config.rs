@@ -0,0 +1,371 @@
+use std::{fmt::Display, fs, io, path::Path, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::{
+    key_value::{self, Entry},
+    routing_rule_by_name, DrawnGames, EntryError, InvalidRules, RoutingRule, Rules, Victory,
+};
+
+///Layout, colours and rules, the defaults are used without it. `--config` reads another file,
+/// the window reloads sizes and colours when it changes.
+pub const CONFIG_FILE: &str = "sttt.conf";
+
+const GAME_ROWS: u32 = 3;
+const GAMES_PER_ROW: u32 = 5;
+const N: u8 = 3;
+const VICTORY: Victory = Victory::InARow(3);
+const DRAWN_GAMES: DrawnGames = DrawnGames::Dead;
+const ROUTING_RULE: &str = "torus";
+
+///Sizes in pixels and colours of the games, only the window draws them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub cell_size: f32,
+    pub cell_gap: f32,
+    pub game_padding: f32,
+    pub game_active_border_width: f32,
+    pub game_gap: f32,
+    ///Space between the boards of two time steps.
+    pub time_step_gap: f32,
+    pub cell_color: Color,
+    pub cell_hover_border_color: Color,
+    ///Also behind the games.
+    pub background_color: Color,
+    pub hover_background_color: Color,
+    pub game_active_border_color: Color,
+    pub x_color: Color,
+    pub o_color: Color,
+    pub strike_color: Color,
+    pub present_color: Color,
+    ///Suggested cell and the game it sends the opponent to, see the hint key.
+    pub hint_color: Color,
+    pub inactive_cell_hover_border_color: Color,
+    pub inactive_hover_background_color: Color,
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            cell_size: 50.0,
+            cell_gap: 3.0,
+            game_padding: 15.0,
+            game_active_border_width: 3.0,
+            game_gap: 5.0,
+            time_step_gap: 60.0,
+            cell_color: Color::WHITE,
+            cell_hover_border_color: Color::Rgba {
+                red: 0.3,
+                green: 0.8,
+                blue: 0.14,
+                alpha: 1.0,
+            },
+            background_color: Color::Rgba {
+                red: 0.15,
+                green: 0.15,
+                blue: 0.15,
+                alpha: 1.0,
+            },
+            hover_background_color: Color::Rgba {
+                red: 0.2,
+                green: 0.28,
+                blue: 0.18,
+                alpha: 1.0,
+            },
+            game_active_border_color: Color::WHITE,
+            x_color: Color::Rgba {
+                red: 0.75,
+                green: 0.1,
+                blue: 0.1,
+                alpha: 1.0,
+            },
+            o_color: Color::Rgba {
+                red: 0.1,
+                green: 0.2,
+                blue: 0.75,
+                alpha: 1.0,
+            },
+            strike_color: Color::Rgba {
+                red: 0.1,
+                green: 0.1,
+                blue: 0.1,
+                alpha: 0.8,
+            },
+            present_color: Color::Rgba {
+                red: 1.0,
+                green: 1.0,
+                blue: 1.0,
+                alpha: 0.05,
+            },
+            hint_color: Color::Rgba {
+                red: 1.0,
+                green: 0.85,
+                blue: 0.2,
+                alpha: 0.6,
+            },
+            inactive_cell_hover_border_color: Color::Rgba {
+                red: 0.9,
+                green: 0.0,
+                blue: 0.0,
+                alpha: 1.0,
+            },
+            inactive_hover_background_color: Color::Rgba {
+                red: 0.32,
+                green: 0.22,
+                blue: 0.24,
+                alpha: 1.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Entry(EntryError),
+    ///The grid set on `line` is too small for the default victory.
+    DefaultVictory {
+        line: usize,
+        victory: Victory,
+        expected: &'static str,
+    },
+}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "{}", error),
+            ConfigError::Entry(error) => write!(f, "{}", error),
+            ConfigError::DefaultVictory {
+                line,
+                victory,
+                expected,
+            } => write!(
+                f,
+                "{}: the default victory `{}` no longer fits the grid, set `victory` to {}",
+                key_value::location(*line),
+                victory,
+                expected
+            ),
+        }
+    }
+}
+impl std::error::Error for ConfigError {}
+impl From<io::Error> for ConfigError {
+    fn from(value: io::Error) -> Self {
+        ConfigError::Io(value)
+    }
+}
+impl From<EntryError> for ConfigError {
+    fn from(value: EntryError) -> Self {
+        ConfigError::Entry(value)
+    }
+}
+
+///Sizes and gaps in pixels.
+fn size(entry: &Entry) -> Result<f32, EntryError> {
+    entry
+        .parse::<f32>("a size of at least 0")
+        .ok()
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .ok_or_else(|| entry.invalid("a size of at least 0"))
+}
+
+///`red green blue` or `red green blue alpha`, each from 0 to 1.
+fn color(entry: &Entry) -> Result<Color, EntryError> {
+    const EXPECTED: &str = "a colour as `red green blue [alpha]` between 0 and 1";
+    let components: Vec<f32> = entry
+        .value
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .map_err(|_| entry.invalid(EXPECTED))?;
+    if components.iter().any(|c| !(0.0..=1.0).contains(c)) {
+        return Err(entry.invalid(EXPECTED));
+    }
+    match components[..] {
+        [red, green, blue] => Ok(Color::rgb(red, green, blue)),
+        [red, green, blue, alpha] => Ok(Color::rgba(red, green, blue, alpha)),
+        _ => Err(entry.invalid(EXPECTED)),
+    }
+}
+
+///Blames the entry of the key that is out of range. `k` defaults to `n`, so a key that was left
+/// out can only be `victory`, whose default is then blamed on the grid size set last.
+fn invalid_rules(entries: &[Entry], victory: Victory, error: InvalidRules) -> ConfigError {
+    if let Some(entry) = entries.iter().find(|entry| entry.key == error.key()) {
+        return entry.invalid(error.expected()).into();
+    }
+    let line = entries
+        .iter()
+        .filter(|entry| matches!(entry.key, "games_per_row" | "game_rows"))
+        .map(|entry| entry.line)
+        .next_back()
+        .unwrap_or(0);
+    ConfigError::DefaultVictory {
+        line,
+        victory,
+        expected: error.expected(),
+    }
+}
+
+///Rules and theme of the match, read at startup.
+#[derive(Clone)]
+pub struct Config {
+    pub rules: Rules,
+    pub routing_rule: Arc<dyn RoutingRule>,
+    pub theme: Theme,
+}
+impl Config {
+    ///Reads `key value` lines, keys that are left out keep their default.
+    ///
+    /// `#` starts a comment, `k` defaults to `n`.
+    /// `overrides` replace the keys of the file, errors name the command line instead of a line.
+    pub fn parse(text: &str, overrides: &[(&str, String)]) -> Result<Self, ConfigError> {
+        let mut games_per_row = GAMES_PER_ROW;
+        let mut game_rows = GAME_ROWS;
+        let mut n = N;
+        let mut k = None;
+        let mut victory = VICTORY;
+        let mut rules = Rules::default().drawn_games(DRAWN_GAMES);
+        let mut routing_rule = routing_rule_by_name(ROUTING_RULE).expect("known routing rule");
+        let mut theme = Theme::default();
+        let mut entries: Vec<Entry> = vec![];
+        for (line, text) in key_value::lines(text) {
+            key_value::push(&mut entries, Entry::new(line, text), &[])?;
+        }
+        for (key, value) in overrides {
+            entries.retain(|entry| entry.key != *key);
+            entries.push(Entry {
+                line: 0,
+                key,
+                value,
+            });
+        }
+        for entry in &entries {
+            match entry.key {
+                "games_per_row" => games_per_row = entry.positive()?,
+                "game_rows" => game_rows = entry.positive()?,
+                "n" => n = entry.positive()?,
+                "k" => k = Some(entry.positive()?),
+                "victory" => {
+                    victory = entry.parse("`in_a_row <count>`, `majority` or `first_to <count>`")?
+                }
+                "drawn_games" => {
+                    rules =
+                        rules.drawn_games(entry.parse("`dead`, `counts_for_both` or `replayable`")?)
+                }
+                "routing_rule" => {
+                    routing_rule = routing_rule_by_name(entry.value).map_err(|_| {
+                        entry.invalid("`torus`, `clamped`, `classic` or `klein_bottle`")
+                    })?
+                }
+                "cell_size" => theme.cell_size = size(entry)?,
+                "cell_gap" => theme.cell_gap = size(entry)?,
+                "game_padding" => theme.game_padding = size(entry)?,
+                "game_active_border_width" => theme.game_active_border_width = size(entry)?,
+                "game_gap" => theme.game_gap = size(entry)?,
+                "time_step_gap" => theme.time_step_gap = size(entry)?,
+                "cell_color" => theme.cell_color = color(entry)?,
+                "cell_hover_border_color" => theme.cell_hover_border_color = color(entry)?,
+                "background_color" => theme.background_color = color(entry)?,
+                "hover_background_color" => theme.hover_background_color = color(entry)?,
+                "game_active_border_color" => theme.game_active_border_color = color(entry)?,
+                "x_color" => theme.x_color = color(entry)?,
+                "o_color" => theme.o_color = color(entry)?,
+                "strike_color" => theme.strike_color = color(entry)?,
+                "present_color" => theme.present_color = color(entry)?,
+                "hint_color" => theme.hint_color = color(entry)?,
+                "inactive_cell_hover_border_color" => {
+                    theme.inactive_cell_hover_border_color = color(entry)?
+                }
+                "inactive_hover_background_color" => {
+                    theme.inactive_hover_background_color = color(entry)?
+                }
+                _ => return Err(entry.unknown().into()),
+            }
+        }
+        let rules = rules
+            .games_per_row(games_per_row)
+            .game_rows(game_rows)
+            .n(n)
+            .k(k.unwrap_or(n))
+            .victory(victory);
+        if let Err(error) = rules.validate() {
+            return Err(invalid_rules(&entries, victory, error));
+        }
+        Ok(Self {
+            rules,
+            routing_rule,
+            theme,
+        })
+    }
+
+    ///Only the `overrides` if there is no file at `path`.
+    pub fn load(path: impl AsRef<Path>, overrides: &[(&str, String)]) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text, overrides),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::parse("", overrides),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self::parse("", &[]).expect("the defaults are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str, overrides: &[(&str, String)]) -> String {
+        Config::parse(text, overrides).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn errors_name_the_key() {
+        assert_eq!(
+            error("n 3\ncell_size -1\n", &[]),
+            "line 2: invalid value `-1` for `cell_size`, expected a size of at least 0"
+        );
+        assert_eq!(
+            error("# layout\nsize 3\n", &[]),
+            "line 2: unknown key `size`"
+        );
+        assert_eq!(
+            error("k 4\nn 3\n", &[]),
+            "line 1: invalid value `4` for `k`, expected from 1 to n"
+        );
+        assert_eq!(
+            error("game_rows 1\nvictory in_a_row 6\n", &[]),
+            "line 2: invalid value `in_a_row 6` for `victory`, \
+             expected `in_a_row` from 1 to the longer side of the grid"
+        );
+    }
+
+    #[test]
+    fn the_default_victory_has_to_fit_the_grid() {
+        assert_eq!(
+            error("games_per_row 1\ngame_rows 1\n", &[]),
+            "line 2: the default victory `in_a_row 3` no longer fits the grid, \
+             set `victory` to `in_a_row` from 1 to the longer side of the grid"
+        );
+        assert!(Config::parse("games_per_row 1\ngame_rows 1\nvictory majority\n", &[]).is_ok());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        assert_eq!(error("n 3\nk 3\nn 4\n", &[]), "line 3: `n` is set twice");
+    }
+
+    #[test]
+    fn overrides_replace_file_keys() {
+        let config = Config::parse("n 4\ncell_size 20\n", &[("n", "5".to_string())]).unwrap();
+        assert_eq!(config.rules, Config::default().rules.n(5).k(5));
+        assert_eq!(config.theme.cell_size, 20.0);
+        assert_eq!(
+            error("n 4\n", &[("n", "0".to_string())]),
+            "command line: invalid value `0` for `n`, expected a positive number"
+        );
+    }
+}
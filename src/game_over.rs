@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use stttwmdtt::GameState;
+
+/// Marker to find the overlay so we can show it once the match is decided
+#[derive(Component)]
+struct GameOverRoot;
+
+/// Marker to find the text entity so we can name the winner
+#[derive(Component)]
+struct GameOverText;
+
+fn setup_game_over(mut commands: Commands) {
+    commands
+        .spawn((
+            GameOverRoot,
+            NodeBundle {
+                background_color: BackgroundColor(Color::BLACK.with_a(0.6)),
+                z_index: ZIndex::Global(i32::MAX - 1),
+                visibility: Visibility::Hidden,
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|root| {
+            root.spawn((
+                GameOverText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 64.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+}
+
+/// Follows the state rather than `MatchOver` events, so an undone win hides the overlay again
+fn show_game_over(
+    state: Res<GameState>,
+    mut q_root: Query<&mut Visibility, With<GameOverRoot>>,
+    mut q_text: Query<&mut Text, With<GameOverText>>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    if let Some(result) = state.result() {
+        for mut text in q_text.iter_mut() {
+            text.sections[0].value = match result.winner {
+                Some(winner) => format!("{} wins!", winner),
+                None => "Draw!".to_string(),
+            };
+        }
+    }
+    for mut visibility in q_root.iter_mut() {
+        *visibility = if state.is_over() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub struct GameOverPlugin;
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_game_over)
+            .add_systems(Update, show_game_over);
+    }
+}
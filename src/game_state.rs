@@ -0,0 +1,1451 @@
+use std::{fmt::Display, str::FromStr, sync::Arc};
+
+use bevy::prelude::*;
+use stttwmdtt_derive::Builder;
+
+use crate::{BoardPosition, GridPosition, MetaBoard, RoutingRule};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Player {
+    #[default]
+    X,
+    O,
+}
+impl Player {
+    pub fn other(self) -> Self {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+}
+impl Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Player::X => write!(f, "X"),
+            Player::O => write!(f, "O"),
+        }
+    }
+}
+impl FromStr for Player {
+    type Err = UnknownValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "X" | "x" => Ok(Player::X),
+            "O" | "o" => Ok(Player::O),
+            _ => Err(UnknownValue(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+///A single n x n game.
+///
+/// Cells are addressed with the same centered coordinates as [GridPosition],
+/// so the middle cell of an odd board is (0, 0).
+pub struct Board {
+    n: u8,
+    cells: Vec<Option<Player>>,
+    winner: Option<Player>,
+    ///Cells of the line that won the game.
+    winning_line: Vec<(i16, i16)>,
+    drawn: bool,
+}
+impl Board {
+    pub fn new(n: u8) -> Self {
+        Self {
+            n,
+            cells: vec![None; n as usize * n as usize],
+            winner: None,
+            winning_line: vec![],
+            drawn: false,
+        }
+    }
+
+    ///A board with the given cells, listed column by column from the bottom left,
+    /// which finds out on its own whether it is won or drawn.
+    pub(crate) fn with_cells(
+        n: u8,
+        k: u8,
+        cells: Vec<Option<Player>>,
+        drawn_games: DrawnGames,
+    ) -> Self {
+        let mut board = Self {
+            cells,
+            ..Self::new(n)
+        };
+        let grid_origin = (n as i64 - 1) / 2;
+        let won = lines(n as i64, n as i64, k).find_map(|line| {
+            let first = board.cells[(line[0].0 * n as i64 + line[0].1) as usize]?;
+            line.iter()
+                .all(|(x, y)| board.cells[(x * n as i64 + y) as usize] == Some(first))
+                .then_some((first, line))
+        });
+        if let Some((player, line)) = won {
+            board.winner = Some(player);
+            board.winning_line = line
+                .into_iter()
+                .map(|(x, y)| ((x - grid_origin) as i16, (y - grid_origin) as i16))
+                .collect();
+        } else if !board.line_possible(k) && drawn_games != DrawnGames::Replayable {
+            board.drawn = true;
+        }
+        board
+    }
+
+    pub fn n(&self) -> u8 {
+        self.n
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        self.winner
+    }
+
+    pub fn winning_line(&self) -> &[(i16, i16)] {
+        &self.winning_line
+    }
+
+    pub fn is_drawn(&self) -> bool {
+        self.drawn
+    }
+
+    fn index(&self, x: i16, y: i16) -> Option<usize> {
+        let grid_origin = (self.n as i16 - 1) / 2;
+        let (x, y) = (x + grid_origin, y + grid_origin);
+        if x < 0 || y < 0 || x >= self.n as i16 || y >= self.n as i16 {
+            return None;
+        }
+        Some(x as usize * self.n as usize + y as usize)
+    }
+
+    pub fn contains(&self, x: i16, y: i16) -> bool {
+        self.index(x, y).is_some()
+    }
+
+    pub fn get(&self, x: i16, y: i16) -> Option<Player> {
+        self.index(x, y).and_then(|i| self.cells[i])
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_some())
+    }
+
+    ///A closed game takes no more moves.
+    pub fn is_closed(&self) -> bool {
+        self.winner.is_some() || self.drawn || self.is_full()
+    }
+
+    fn place(&mut self, x: i16, y: i16, player: Player) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = Some(player);
+        }
+    }
+
+    ///Longest run of `player` through (x, y) that is at least `k` cells long,
+    /// looking along rows, columns and both diagonals.
+    fn line_through(&self, x: i16, y: i16, k: u8, player: Player) -> Option<Vec<(i16, i16)>> {
+        [(1, 0), (0, 1), (1, 1), (1, -1)]
+            .into_iter()
+            .map(|(dx, dy)| {
+                let mut start = (x, y);
+                while self.get(start.0 - dx, start.1 - dy) == Some(player) {
+                    start = (start.0 - dx, start.1 - dy);
+                }
+                let mut line = vec![start];
+                let mut next = (start.0 + dx, start.1 + dy);
+                while self.get(next.0, next.1) == Some(player) {
+                    line.push(next);
+                    next = (next.0 + dx, next.1 + dy);
+                }
+                line
+            })
+            .filter(|line| line.len() >= k as usize)
+            .max_by_key(|line| line.len())
+    }
+
+    ///Whether any player can still get `k` in a row.
+    fn line_possible(&self, k: u8) -> bool {
+        let n = self.n as i64;
+        lines(n, n, k).any(|line| {
+            let marks: Vec<_> = line
+                .into_iter()
+                .map(|(x, y)| self.cells[(x * n + y) as usize])
+                .collect();
+            !(marks.contains(&Some(Player::X)) && marks.contains(&Some(Player::O)))
+        })
+    }
+
+    fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = None);
+    }
+}
+
+///Every straight line of `k` cells on a `width` x `height` grid.
+pub(crate) fn lines(width: i64, height: i64, k: u8) -> impl Iterator<Item = Vec<(i64, i64)>> {
+    let k = k as i64;
+    (0..width)
+        .flat_map(move |x| (0..height).map(move |y| (x, y)))
+        .flat_map(move |(x, y)| {
+            [(1, 0), (0, 1), (1, 1), (1, -1)]
+                .into_iter()
+                .map(move |(dx, dy)| (0..k).map(|i| (x + i * dx, y + i * dy)).collect::<Vec<_>>())
+        })
+        .filter(move |line| {
+            line.iter()
+                .all(|(x, y)| *x >= 0 && *y >= 0 && *x < width && *y < height)
+        })
+}
+
+///How won games decide the whole match.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Victory {
+    ///Won games in a row on the games_per_row x game_rows meta board.
+    InARow(u8),
+    ///More than half of all games.
+    Majority,
+    ///The first player to win this many games.
+    FirstTo(u32),
+}
+
+impl Display for Victory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Victory::InARow(k) => write!(f, "in_a_row {}", k),
+            Victory::Majority => write!(f, "majority"),
+            Victory::FirstTo(count) => write!(f, "first_to {}", count),
+        }
+    }
+}
+impl FromStr for Victory {
+    type Err = UnknownValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || UnknownValue(s.to_string());
+        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["in_a_row", k] => k.parse().map(Victory::InARow).map_err(|_| unknown()),
+            ["majority"] => Ok(Victory::Majority),
+            ["first_to", count] => count.parse().map(Victory::FirstTo).map_err(|_| unknown()),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+///What a drawn game means for the meta board.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DrawnGames {
+    ///Counts for nobody.
+    Dead,
+    ///Counts as won for both players.
+    CountsForBoth,
+    ///Is cleared and played again.
+    Replayable,
+}
+
+impl Display for DrawnGames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawnGames::Dead => write!(f, "dead"),
+            DrawnGames::CountsForBoth => write!(f, "counts_for_both"),
+            DrawnGames::Replayable => write!(f, "replayable"),
+        }
+    }
+}
+impl FromStr for DrawnGames {
+    type Err = UnknownValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "dead" => Ok(DrawnGames::Dead),
+            "counts_for_both" => Ok(DrawnGames::CountsForBoth),
+            "replayable" => Ok(DrawnGames::Replayable),
+            _ => Err(UnknownValue(s.to_string())),
+        }
+    }
+}
+
+///Text that names no known value.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnknownValue(pub String);
+impl Display for UnknownValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown value `{}`", self.0)
+    }
+}
+impl std::error::Error for UnknownValue {}
+
+#[derive(Builder, Clone, PartialEq, Eq, Debug)]
+pub struct Rules {
+    games_per_row: u32,
+    game_rows: u32,
+    n: u8,
+    ///Marks in a row needed to win a game.
+    k: u8,
+    victory: Victory,
+    drawn_games: DrawnGames,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            games_per_row: 1,
+            game_rows: 1,
+            n: 3,
+            k: 3,
+            //Valid on any grid, unlike a number of games in a row.
+            victory: Victory::Majority,
+            drawn_games: DrawnGames::Dead,
+        }
+    }
+}
+impl Rules {
+    ///Rejects rules under which no game or no match could ever be won.
+    pub fn validate(&self) -> Result<(), InvalidRules> {
+        if self.k == 0 || self.k > self.n {
+            return Err(InvalidRules::KOutOfRange);
+        }
+        let longest = self.games_per_row.max(self.game_rows);
+        let games = self.games_per_row as u64 * self.game_rows as u64;
+        match self.victory {
+            Victory::InARow(k) if k == 0 || k as u32 > longest => Err(InvalidRules::InARow),
+            Victory::FirstTo(count) if count == 0 || count as u64 > games => {
+                Err(InvalidRules::FirstTo)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+///Rules that can not be played, see [Rules::validate].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InvalidRules {
+    ///`k` is 0 or more than fits into a game.
+    KOutOfRange,
+    ///More games in a row than the grid is long.
+    InARow,
+    ///More games than there are.
+    FirstTo,
+}
+impl InvalidRules {
+    ///The key of config and save files with the wrong value.
+    pub fn key(&self) -> &'static str {
+        match self {
+            InvalidRules::KOutOfRange => "k",
+            InvalidRules::InARow | InvalidRules::FirstTo => "victory",
+        }
+    }
+
+    pub fn expected(&self) -> &'static str {
+        match self {
+            InvalidRules::KOutOfRange => "from 1 to n",
+            InvalidRules::InARow => "`in_a_row` from 1 to the longer side of the grid",
+            InvalidRules::FirstTo => "`first_to` from 1 to the number of games",
+        }
+    }
+}
+impl Display for InvalidRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` should be {}", self.key(), self.expected())
+    }
+}
+impl std::error::Error for InvalidRules {}
+
+#[derive(Event, PartialEq, Clone, Debug)]
+pub struct BoardWon {
+    pub game_id: BoardPosition,
+    pub player: Player,
+    pub line: Vec<GridPosition>,
+}
+
+#[derive(Event, PartialEq, Clone, Debug)]
+pub struct BoardDrawn {
+    pub game_id: BoardPosition,
+}
+
+#[derive(Event, PartialEq, Clone, Debug)]
+pub struct MatchOver {
+    ///`None` for a drawn match.
+    pub winner: Option<Player>,
+    ///The games that decided the match.
+    pub games: Vec<BoardPosition>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct MoveOutcome {
+    pub position: GridPosition,
+    pub player: Player,
+    pub previous_game: Option<BoardPosition>,
+    pub active_game: Option<BoardPosition>,
+    pub board_won: Option<BoardWon>,
+    pub board_drawn: Option<BoardDrawn>,
+    pub match_over: Option<MatchOver>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum MoveError {
+    OutOfBounds(GridPosition),
+    Occupied(GridPosition),
+    ClosedGame(BoardPosition),
+    ///The game lies on a time step where it was not the current players turn.
+    Unreachable(BoardPosition),
+    ///The turn cannot be submitted before these games got a move.
+    Obligations(Vec<BoardPosition>),
+    InactiveGame {
+        game: BoardPosition,
+        active: BoardPosition,
+    },
+    MatchOver,
+}
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::OutOfBounds(pos) => write!(f, "{} is not on the board", pos),
+            MoveError::Occupied(pos) => write!(f, "{} is already marked", pos),
+            MoveError::ClosedGame(game) => write!(f, "game {} is already decided", game),
+            MoveError::Unreachable(game) => {
+                write!(f, "game {} cannot be reached by the current player", game)
+            }
+            MoveError::InactiveGame { game, active } => {
+                write!(f, "game {} is not active, play in game {}", game, active)
+            }
+            MoveError::Obligations(games) => {
+                write!(f, "the turn is not over, play in ")?;
+                for (i, game) in games.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "game {}", game)?;
+                }
+                Ok(())
+            }
+            MoveError::MatchOver => write!(f, "the match is already over"),
+        }
+    }
+}
+impl std::error::Error for MoveError {}
+
+///Something a player did, in the order they did it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Move(GridPosition),
+    Submit,
+}
+
+#[derive(Clone, Debug)]
+struct Record {
+    action: Action,
+    ///Timeline the snapshot of a move was added to.
+    timeline: u32,
+}
+
+#[derive(Clone, Debug)]
+///All games at one time step.
+pub(crate) struct Snapshot {
+    pub(crate) boards: Vec<Board>,
+    ///(column, row) of the game to play in next, `None` lets the player choose any open game.
+    pub(crate) active_game: Option<(u32, u32)>,
+    pub(crate) current_player: Player,
+}
+impl Snapshot {
+    fn is_active(&self, column: u32, row: u32, board: &Board) -> bool {
+        match self.active_game {
+            Some(active) => active == (column, row),
+            None => !board.is_closed(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+///One branch of history, a new one starts with every move on a past time step.
+pub(crate) struct Timeline {
+    ///(timeline, time) of the snapshot this timeline branched off from.
+    pub(crate) parent: Option<(u32, u32)>,
+    ///Time step of the first snapshot.
+    pub(crate) start: u32,
+    ///Never empty, the last snapshot is the head of the timeline.
+    pub(crate) snapshots: Vec<Snapshot>,
+}
+impl Timeline {
+    fn end(&self) -> u32 {
+        self.start + self.snapshots.len() as u32 - 1
+    }
+
+    fn head(&self) -> &Snapshot {
+        &self.snapshots[self.snapshots.len() - 1]
+    }
+
+    fn get(&self, time: u32) -> Option<&Snapshot> {
+        time.checked_sub(self.start)
+            .and_then(|i| self.snapshots.get(i as usize))
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+///The rules of the game without any rendering.
+///
+/// Every move adds a new time step to the history, earlier time steps are never changed.
+/// Moving on a past time step branches off a new timeline instead.
+/// A player may move several times before passing the turn with [GameState::submit_turn].
+/// Boards are stored by their [BoardPosition::game_id].
+pub struct GameState {
+    rules: Rules,
+    routing_rule: Arc<dyn RoutingRule>,
+    ///Indexed by [BoardPosition::timeline], never empty.
+    timelines: Vec<Timeline>,
+    current_player: Player,
+    result: Option<MatchOver>,
+    done: Vec<Record>,
+    ///Undone actions, the next one to redo last.
+    undone: Vec<Action>,
+    ///The [position](crate::position) before the first action, `None` for empty boards.
+    start_position: Option<String>,
+}
+impl GameState {
+    pub fn new(rules: Rules, routing_rule: Arc<dyn RoutingRule>) -> Self {
+        let games = rules.games_per_row as u64 * rules.game_rows as u64;
+        let start = BoardPosition::from_game_id(games / 2, rules.game_rows);
+        Self {
+            routing_rule,
+            timelines: vec![Timeline {
+                parent: None,
+                start: 0,
+                snapshots: vec![Snapshot {
+                    boards: (0..games).map(|_| Board::new(rules.n)).collect(),
+                    active_game: Some((start.column, start.row)),
+                    current_player: Player::X,
+                }],
+            }],
+            current_player: Player::X,
+            result: None,
+            done: vec![],
+            undone: vec![],
+            start_position: None,
+            rules,
+        }
+    }
+
+    ///The match with its timelines replaced, without any actions to undo.
+    pub(crate) fn with_timelines(
+        mut self,
+        current_player: Player,
+        timelines: Vec<Timeline>,
+    ) -> Self {
+        self.timelines = timelines;
+        self.current_player = current_player;
+        self.done.clear();
+        self.undone.clear();
+        self.result = (0..self.timeline_count())
+            .find_map(|timeline| self.decide_match(current_player.other(), timeline));
+        self.start_position = Some(self.position());
+        self
+    }
+
+    ///The [position](crate::position) the actions start from, `None` if the match started with
+    /// empty boards.
+    pub fn start_position(&self) -> Option<&str> {
+        self.start_position.as_deref()
+    }
+
+    pub(crate) fn timelines(&self) -> &[Timeline] {
+        &self.timelines
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn routing_rule(&self) -> &dyn RoutingRule {
+        self.routing_rule.as_ref()
+    }
+
+    pub fn meta_board(&self) -> MetaBoard {
+        MetaBoard {
+            games_per_row: self.rules.games_per_row,
+            game_rows: self.rules.game_rows,
+            n: self.rules.n,
+        }
+    }
+
+    pub fn games_per_row(&self) -> u32 {
+        self.rules.games_per_row
+    }
+
+    pub fn game_rows(&self) -> u32 {
+        self.rules.game_rows
+    }
+
+    pub fn n(&self) -> u8 {
+        self.rules.n
+    }
+
+    pub fn k(&self) -> u8 {
+        self.rules.k
+    }
+
+    pub fn victory(&self) -> Victory {
+        self.rules.victory
+    }
+
+    pub fn drawn_games(&self) -> DrawnGames {
+        self.rules.drawn_games
+    }
+
+    pub fn timeline_count(&self) -> u32 {
+        self.timelines.len() as u32
+    }
+
+    ///(timeline, time) of the time step `timeline` branched off from.
+    pub fn parent(&self, timeline: u32) -> Option<(u32, u32)> {
+        self.timelines
+            .get(timeline as usize)
+            .and_then(|timeline| timeline.parent)
+    }
+
+    ///First time step of `timeline`.
+    pub fn start(&self, timeline: u32) -> Option<u32> {
+        self.timelines
+            .get(timeline as usize)
+            .map(|timeline| timeline.start)
+    }
+
+    ///Latest time step of `timeline`.
+    pub fn end(&self, timeline: u32) -> Option<u32> {
+        self.timelines.get(timeline as usize).map(Timeline::end)
+    }
+
+    ///(timeline, time) of every snapshot.
+    pub fn time_steps(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.timelines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, timeline)| (timeline.start..=timeline.end()).map(move |t| (i as u32, t)))
+    }
+
+    fn snapshot(&self, timeline: u32, time: u32) -> Option<&Snapshot> {
+        self.timelines.get(timeline as usize)?.get(time)
+    }
+
+    ///The game to play in next on the latest time step of `timeline`.
+    pub fn active_game(&self, timeline: u32) -> Option<BoardPosition> {
+        let time = self.end(timeline)?;
+        self.timelines[timeline as usize]
+            .head()
+            .active_game
+            .map(|(column, row)| BoardPosition::new(column, row).at(time, timeline))
+    }
+
+    ///All games on the latest time steps the current player may play in.
+    pub fn active_games(&self) -> Vec<BoardPosition> {
+        (0..self.timeline_count())
+            .flat_map(|timeline| self.games_at(self.end(timeline).unwrap_or_default(), timeline))
+            .filter(|game| self.is_reachable(*game))
+            .collect()
+    }
+
+    ///Every game the current player may play in, the [GameState::active_games] as well as the
+    /// games of earlier time steps a move would branch off from.
+    pub fn reachable_games(&self) -> Vec<BoardPosition> {
+        self.time_steps()
+            .flat_map(|(timeline, time)| self.games_at(time, timeline))
+            .filter(|game| self.is_reachable(*game))
+            .collect()
+    }
+
+    ///Whether the current player may play in `game`, which starts a new timeline if `game`
+    /// lies in the past.
+    ///
+    /// Only time steps where it was the current players turn can be reached, and only in the
+    /// games that were active back then.
+    pub fn is_reachable(&self, game: BoardPosition) -> bool {
+        let (Some(snapshot), Some(index)) =
+            (self.snapshot(game.timeline, game.time), self.index(game))
+        else {
+            return false;
+        };
+        let board = &snapshot.boards[index];
+        !self.is_over()
+            && snapshot.current_player == self.current_player
+            && snapshot.is_active(game.column, game.row, board)
+            && !board.is_closed()
+    }
+
+    pub fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    ///The earliest time step any timeline ends on.
+    ///
+    /// Timelines ending on the present are unresolved until they got a move.
+    pub fn present(&self) -> u32 {
+        self.timelines
+            .iter()
+            .map(Timeline::end)
+            .min()
+            .unwrap_or_default()
+    }
+
+    ///Games on the present the current player still has to play in before the turn passes.
+    pub fn obligations(&self) -> Vec<BoardPosition> {
+        let present = self.present();
+        (0..self.timeline_count())
+            .filter(|timeline| self.end(*timeline) == Some(present))
+            .flat_map(|timeline| self.games_at(present, timeline))
+            .filter(|game| self.is_reachable(*game))
+            .collect()
+    }
+
+    pub fn can_submit(&self) -> bool {
+        !self.is_over() && self.obligations().is_empty()
+    }
+
+    ///Passes the turn to the other player once every timeline on the present got a move.
+    pub fn submit_turn(&mut self) -> Result<Player, MoveError> {
+        if self.is_over() {
+            return Err(MoveError::MatchOver);
+        }
+        let obligations = self.obligations();
+        if !obligations.is_empty() {
+            return Err(MoveError::Obligations(obligations));
+        }
+        self.current_player = self.current_player.other();
+        self.record(Action::Submit, 0);
+        Ok(self.current_player)
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        self.result.as_ref().and_then(|result| result.winner)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    pub fn result(&self) -> Option<&MatchOver> {
+        self.result.as_ref()
+    }
+
+    ///Every action since the start of the match.
+    pub fn actions(&self) -> impl Iterator<Item = Action> + '_ {
+        self.done.iter().map(|record| record.action)
+    }
+
+    ///Actions [GameState::redo] would play, the next one first.
+    pub fn undone_actions(&self) -> impl Iterator<Item = Action> + '_ {
+        self.undone.iter().rev().copied()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    ///Takes back the last action, a move takes its snapshot and possibly its timeline with it.
+    pub fn undo(&mut self) -> Option<Action> {
+        let record = self.done.pop()?;
+        match record.action {
+            Action::Move(_) => {
+                let timeline = &mut self.timelines[record.timeline as usize];
+                timeline.snapshots.pop();
+                if timeline.snapshots.is_empty() {
+                    self.timelines.pop();
+                }
+            }
+            Action::Submit => self.current_player = self.current_player.other(),
+        }
+        self.result = None;
+        self.undone.push(record.action);
+        Some(record.action)
+    }
+
+    ///Plays the last undone action again.
+    pub fn redo(&mut self) -> Option<Result<Action, MoveError>> {
+        let action = *self.undone.last()?;
+        Some(match action {
+            Action::Move(pos) => self.apply_move(pos).map(|_| action),
+            Action::Submit => self.submit_turn().map(|_| action),
+        })
+    }
+
+    ///Takes back the last move of a human, returns the earliest action taken back.
+    ///
+    /// Computer moves and passed turns after that move go with it: moves of players for which
+    /// `is_computer` holds are taken back up to the last human move and with `auto_submit` a
+    /// passed turn goes together with the move before it. Earlier moves of the same turn stay.
+    pub fn undo_turn(
+        &mut self,
+        auto_submit: bool,
+        is_computer: impl Fn(Player) -> bool,
+    ) -> Option<Action> {
+        let mut undone = None;
+        while let Some(mut action) = self.undo() {
+            while auto_submit && action == Action::Submit {
+                match self.undo() {
+                    Some(previous) => action = previous,
+                    None => break,
+                }
+            }
+            undone = Some(action);
+            if !is_computer(self.current_player) || !self.can_undo() {
+                break;
+            }
+        }
+        undone
+    }
+
+    ///Keeps the undone actions as long as the same actions are played again.
+    fn record(&mut self, action: Action, timeline: u32) {
+        if self.undone.last() == Some(&action) {
+            self.undone.pop();
+        } else {
+            self.undone.clear();
+        }
+        self.done.push(Record { action, timeline });
+    }
+
+    ///The games on the latest time step of every timeline.
+    pub fn games(&self) -> impl Iterator<Item = BoardPosition> + '_ {
+        (0..self.timeline_count())
+            .flat_map(|timeline| self.games_at(self.end(timeline).unwrap_or_default(), timeline))
+    }
+
+    pub fn games_at(&self, time: u32, timeline: u32) -> impl Iterator<Item = BoardPosition> {
+        let game_rows = self.rules.game_rows;
+        let games = self.rules.games_per_row as u64 * game_rows as u64;
+        (0..games).map(move |id| BoardPosition::from_game_id(id, game_rows).at(time, timeline))
+    }
+
+    pub fn board(&self, game: BoardPosition) -> Option<&Board> {
+        let index = self.index(game)?;
+        self.snapshot(game.timeline, game.time)
+            .map(|snapshot| &snapshot.boards[index])
+    }
+
+    fn index(&self, game: BoardPosition) -> Option<usize> {
+        if game.column >= self.rules.games_per_row || game.row >= self.rules.game_rows {
+            return None;
+        }
+        Some(game.game_id(self.rules.game_rows) as usize)
+    }
+
+    ///Plays a move, the played game moves on to the next time step.
+    ///
+    /// Moves on the latest time step of a timeline extend it, moves in the past branch off a
+    /// new timeline.
+    pub fn apply_move(&mut self, pos: GridPosition) -> Result<MoveOutcome, MoveError> {
+        if self.is_over() {
+            return Err(MoveError::MatchOver);
+        }
+        let game = pos.board();
+        let (Some(snapshot), Some(index)) =
+            (self.snapshot(game.timeline, game.time), self.index(game))
+        else {
+            return Err(MoveError::OutOfBounds(pos));
+        };
+        if !snapshot.boards[index].contains(pos.x, pos.y) {
+            return Err(MoveError::OutOfBounds(pos));
+        }
+        if snapshot.current_player != self.current_player {
+            return Err(MoveError::Unreachable(game));
+        }
+        let previous_game = snapshot
+            .active_game
+            .map(|(column, row)| BoardPosition::new(column, row).at(game.time, game.timeline));
+        if let Some(active) = previous_game.filter(|active| *active != game) {
+            return Err(MoveError::InactiveGame { game, active });
+        }
+        if snapshot.boards[index].is_closed() {
+            return Err(MoveError::ClosedGame(game));
+        }
+        if snapshot.boards[index].get(pos.x, pos.y).is_some() {
+            return Err(MoveError::Occupied(pos));
+        }
+        let mut next = snapshot.clone();
+        let player = self.current_player;
+        let k = self.rules.k;
+        let timeline = if Some(game.time) == self.end(game.timeline) {
+            game.timeline
+        } else {
+            self.timeline_count()
+        };
+        let moved = game.at(game.time + 1, timeline);
+        let board = &mut next.boards[index];
+        board.place(pos.x, pos.y, player);
+        let board_won = if board.winner.is_none() {
+            board.line_through(pos.x, pos.y, k, player).map(|line| {
+                board.winner = Some(player);
+                board.winning_line = line.clone();
+                BoardWon {
+                    game_id: moved,
+                    player,
+                    line: line.into_iter().map(|(x, y)| moved.cell(x, y)).collect(),
+                }
+            })
+        } else {
+            None
+        };
+        let board_drawn = if board_won.is_none() && !board.line_possible(k) {
+            match self.rules.drawn_games {
+                DrawnGames::Replayable => board.clear(),
+                _ => board.drawn = true,
+            }
+            Some(BoardDrawn { game_id: moved })
+        } else {
+            None
+        };
+        next.current_player = player.other();
+        let (column, row) = self.next_game(&pos);
+        let next_id = BoardPosition::new(column, row).game_id(self.rules.game_rows) as usize;
+        next.active_game = Some((column, row)).filter(|_| !next.boards[next_id].is_closed());
+        if timeline == game.timeline {
+            self.timelines[timeline as usize].snapshots.push(next);
+        } else {
+            self.timelines.push(Timeline {
+                parent: Some((game.timeline, game.time)),
+                start: moved.time,
+                snapshots: vec![next],
+            });
+        }
+        let match_over = if board_won.is_some() || board_drawn.is_some() {
+            self.decide_match(player, timeline)
+        } else {
+            None
+        };
+        self.result = match_over.clone();
+        self.record(Action::Move(pos), timeline);
+        Ok(MoveOutcome {
+            position: pos,
+            player,
+            previous_game,
+            active_game: self.active_game(timeline),
+            board_won,
+            board_drawn,
+            match_over,
+        })
+    }
+
+    ///A win on the latest boards of `timeline`, or a draw once the latest boards of no timeline
+    /// can be won by either player.
+    ///
+    /// `player`, who just moved, is checked first, as a game counting for both can finish a
+    /// line for either player.
+    fn decide_match(&self, player: Player, timeline: u32) -> Option<MatchOver> {
+        let boards = &self.timelines[timeline as usize].head().boards;
+        let time = self.timelines[timeline as usize].end();
+        for player in [player, player.other()] {
+            if let Some(games) = self.winning_games(boards, player) {
+                return Some(MatchOver {
+                    winner: Some(player),
+                    games: games
+                        .into_iter()
+                        .map(|id| {
+                            BoardPosition::from_game_id(id, self.rules.game_rows).at(time, timeline)
+                        })
+                        .collect(),
+                });
+            }
+        }
+        let winnable = self.timelines.iter().any(|timeline| {
+            let boards = &timeline.head().boards;
+            self.can_still_win(boards, Player::X) || self.can_still_win(boards, Player::O)
+        });
+        (!winnable).then_some(MatchOver {
+            winner: None,
+            games: vec![],
+        })
+    }
+
+    fn counts_for(&self, board: &Board, player: Player) -> bool {
+        board.winner == Some(player)
+            || board.drawn && self.rules.drawn_games == DrawnGames::CountsForBoth
+    }
+
+    fn winning_games(&self, boards: &[Board], player: Player) -> Option<Vec<u64>> {
+        let counts = |id: &u64| self.counts_for(&boards[*id as usize], player);
+        let won_by = |id: &u64| boards[*id as usize].winner == Some(player);
+        let counted: Vec<u64> = (0..boards.len() as u64).filter(counts).collect();
+        match self.rules.victory {
+            Victory::InARow(k) => self
+                .meta_lines(k)
+                .find(|line| line.iter().all(counts) && line.iter().any(won_by)),
+            Victory::Majority => {
+                (counted.len() * 2 > boards.len() && counted.iter().any(won_by)).then_some(counted)
+            }
+            Victory::FirstTo(count) => {
+                (counted.len() >= count as usize && counted.iter().any(won_by)).then_some(counted)
+            }
+        }
+    }
+
+    ///Games drawn for both players only complete a win together with a game actually won.
+    fn can_still_win(&self, boards: &[Board], player: Player) -> bool {
+        let open = |id: &u64| !boards[*id as usize].is_closed();
+        let possible = |id: &u64| self.counts_for(&boards[*id as usize], player) || open(id);
+        let decisive = |id: &u64| boards[*id as usize].winner == Some(player) || open(id);
+        let games = 0..boards.len() as u64;
+        let possible_games = games.clone().filter(possible).count();
+        let any_decisive = games.clone().any(|id| decisive(&id));
+        match self.rules.victory {
+            Victory::InARow(k) => self
+                .meta_lines(k)
+                .any(|line| line.iter().all(possible) && line.iter().any(decisive)),
+            Victory::Majority => any_decisive && possible_games * 2 > boards.len(),
+            Victory::FirstTo(count) => any_decisive && possible_games >= count as usize,
+        }
+    }
+
+    fn meta_lines(&self, k: u8) -> impl Iterator<Item = Vec<u64>> {
+        let game_rows = self.rules.game_rows as i64;
+        lines(self.rules.games_per_row as i64, game_rows, k).map(move |line| {
+            line.into_iter()
+                .map(|(column, row)| (column * game_rows + row) as u64)
+                .collect()
+        })
+    }
+
+    fn next_game(&self, pos: &GridPosition) -> (u32, u32) {
+        self.routing_rule
+            .route(self.meta_board(), pos.column, pos.row, pos.x, pos.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Clamped, Classic, KleinBottle, Torus};
+
+    fn rules(games_per_row: u32, game_rows: u32, n: u8, k: u8) -> Rules {
+        Rules::default()
+            .games_per_row(games_per_row)
+            .game_rows(game_rows)
+            .n(n)
+            .k(k)
+    }
+
+    fn game(column: u32, row: u32) -> BoardPosition {
+        BoardPosition::new(column, row)
+    }
+
+    ///The game at (column, row) on the latest time step of the first timeline.
+    fn present(state: &GameState, column: u32, row: u32) -> BoardPosition {
+        game(column, row).at(state.end(0).unwrap(), 0)
+    }
+
+    ///Plays (x, y) in the game at (column, row) on the latest time step of the first timeline
+    /// and passes the turn, unless the move ended the match.
+    fn turn(state: &mut GameState, column: u32, row: u32, x: i16, y: i16) -> MoveOutcome {
+        let outcome = state
+            .apply_move(present(state, column, row).cell(x, y))
+            .unwrap();
+        if !state.is_over() {
+            state.submit_turn().unwrap();
+        }
+        outcome
+    }
+
+    #[test]
+    fn moves_outside_the_boards() {
+        let mut state = GameState::new(rules(3, 3, 3, 3), Arc::new(Torus));
+        for pos in [game(1, 1).cell(2, 0), game(3, 0).cell(0, 0)] {
+            assert_eq!(state.apply_move(pos), Err(MoveError::OutOfBounds(pos)));
+        }
+    }
+
+    #[test]
+    fn moves_outside_the_active_game() {
+        let mut state = GameState::new(rules(3, 3, 3, 3), Arc::new(Torus));
+        assert_eq!(state.active_game(0), Some(game(1, 1)));
+        assert_eq!(
+            state.apply_move(present(&state, 0, 0).cell(0, 0)),
+            Err(MoveError::InactiveGame {
+                game: game(0, 0),
+                active: game(1, 1),
+            })
+        );
+    }
+
+    ///The (column, row) of the active game after each of `cells`, played in the active game of
+    /// a 3x3 grid.
+    fn route(routing_rule: Arc<dyn RoutingRule>, cells: &[(i16, i16)]) -> Vec<(u32, u32)> {
+        let mut state = GameState::new(rules(3, 3, 3, 3), routing_rule);
+        cells
+            .iter()
+            .map(|(x, y)| {
+                let active = state.active_game(0).unwrap();
+                let next = state.apply_move(active.cell(*x, *y)).unwrap();
+                state.submit_turn().unwrap();
+                let next = next.active_game.unwrap();
+                (next.column, next.row)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn routing_at_the_edges() {
+        let corner = [(1, 1), (1, 1)];
+        assert_eq!(route(Arc::new(Torus), &corner), [(2, 2), (0, 0)]);
+        assert_eq!(route(Arc::new(Clamped), &corner), [(2, 2), (2, 2)]);
+        assert_eq!(
+            route(Arc::new(Classic), &[(-1, 1), (1, -1), (0, 0)]),
+            [(0, 2), (2, 0), (1, 1)]
+        );
+        //Leaving over the top mirrors the column.
+        assert_eq!(
+            route(Arc::new(KleinBottle), &[(-1, 1), (-1, 1)]),
+            [(0, 2), (0, 0)]
+        );
+        assert_eq!(
+            route(Arc::new(Torus), &[(-1, 1), (-1, 1)]),
+            [(0, 2), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn players_take_turns() {
+        let mut state = GameState::new(rules(1, 1, 3, 3), Arc::new(Torus));
+        assert_eq!(state.current_player(), Player::X);
+        let outcome = state.apply_move(present(&state, 0, 0).cell(0, 0)).unwrap();
+        assert_eq!(outcome.player, Player::X);
+        assert_eq!(state.current_player(), Player::X);
+        assert_eq!(state.submit_turn(), Ok(Player::O));
+        assert_eq!(
+            state.apply_move(present(&state, 0, 0).cell(0, 0)),
+            Err(MoveError::Occupied(present(&state, 0, 0).cell(0, 0)))
+        );
+        let outcome = turn(&mut state, 0, 0, 1, -1);
+        assert_eq!(outcome.player, Player::O);
+        let board = state.board(present(&state, 0, 0)).unwrap();
+        assert_eq!(board.get(0, 0), Some(Player::X));
+        assert_eq!(board.get(1, -1), Some(Player::O));
+        assert_eq!(state.current_player(), Player::X);
+    }
+
+    #[test]
+    fn k_in_a_row_wins_a_game() {
+        let mut state = GameState::new(rules(1, 1, 4, 3), Arc::new(Torus));
+        for (x, y) in [(-1, -1), (-1, 2), (0, 0), (0, 2)] {
+            let outcome = turn(&mut state, 0, 0, x, y);
+            assert_eq!(outcome.board_won, None);
+        }
+        let outcome = turn(&mut state, 0, 0, 1, 1);
+        assert_eq!(
+            outcome.board_won,
+            Some(BoardWon {
+                game_id: present(&state, 0, 0),
+                player: Player::X,
+                line: vec![
+                    present(&state, 0, 0).cell(-1, -1),
+                    present(&state, 0, 0).cell(0, 0),
+                    present(&state, 0, 0).cell(1, 1),
+                ],
+            })
+        );
+        assert_eq!(
+            state.board(present(&state, 0, 0)).unwrap().winner(),
+            Some(Player::X)
+        );
+    }
+
+    #[test]
+    fn past_time_steps_keep_their_boards() {
+        let mut state = GameState::new(rules(1, 1, 3, 3), Arc::new(Torus));
+        turn(&mut state, 0, 0, 0, 0);
+        assert_eq!(state.end(0), Some(1));
+        assert_eq!(state.board(game(0, 0)).unwrap().get(0, 0), None);
+        assert_eq!(
+            state.board(present(&state, 0, 0)).unwrap().get(0, 0),
+            Some(Player::X)
+        );
+        assert_eq!(
+            state.apply_move(game(0, 0).cell(1, 1)),
+            Err(MoveError::Unreachable(game(0, 0)))
+        );
+        assert_eq!(state.board(game(0, 0).at(2, 0)), None);
+    }
+
+    ///X moves on the first time step after both players moved once, and passes the turn.
+    fn branched() -> GameState {
+        let mut state = GameState::new(rules(1, 1, 3, 3), Arc::new(Torus));
+        turn(&mut state, 0, 0, 0, 0);
+        turn(&mut state, 0, 0, 1, 1);
+        let outcome = state.apply_move(game(0, 0).cell(-1, -1)).unwrap();
+        assert_eq!(outcome.active_game, Some(game(0, 0).at(1, 1)));
+        assert_eq!(state.submit_turn(), Ok(Player::O));
+        state
+    }
+
+    #[test]
+    fn moves_in_the_past_branch_a_new_timeline() {
+        let mut state = branched();
+        assert_eq!(state.timeline_count(), 2);
+        assert_eq!(state.parent(1), Some((0, 0)));
+        assert_eq!((state.start(1), state.end(1)), (Some(1), Some(1)));
+        assert_eq!(state.end(0), Some(2));
+        let board = state.board(game(0, 0).at(1, 1)).unwrap();
+        assert_eq!(board.get(-1, -1), Some(Player::X));
+        assert_eq!(board.get(0, 0), None);
+        //O moved on the second time step of the first timeline, but not on the third.
+        assert!(state.is_reachable(game(0, 0).at(1, 0)));
+        assert!(!state.is_reachable(game(0, 0).at(2, 0)));
+        assert_eq!(
+            state.apply_move(game(0, 0).cell(1, 1)),
+            Err(MoveError::Unreachable(game(0, 0)))
+        );
+    }
+
+    #[test]
+    fn new_timelines_must_be_answered() {
+        let mut state = branched();
+        assert_eq!(state.present(), 1);
+        let obligations = vec![game(0, 0).at(1, 1)];
+        assert_eq!(state.obligations(), obligations);
+        assert!(!state.can_submit());
+        assert_eq!(
+            state.submit_turn(),
+            Err(MoveError::Obligations(obligations))
+        );
+        state.apply_move(game(0, 0).at(1, 1).cell(0, 0)).unwrap();
+        assert_eq!(state.present(), 2);
+        assert!(state.obligations().is_empty());
+        assert_eq!(state.submit_turn(), Ok(Player::X));
+    }
+
+    ///Every move wins its one cell game and lets the opponent choose, on a row of five games.
+    fn row_of_five(victory: Victory) -> GameState {
+        GameState::new(rules(5, 1, 1, 1).victory(victory), Arc::new(Torus))
+    }
+
+    fn play(state: &mut GameState, column: u32) -> MoveOutcome {
+        turn(state, column, 0, 0, 0)
+    }
+
+    #[test]
+    fn in_a_row_victory() {
+        let mut state = row_of_five(Victory::InARow(2));
+        play(&mut state, 2);
+        play(&mut state, 0);
+        let outcome = play(&mut state, 3);
+        assert_eq!(
+            outcome.match_over,
+            Some(MatchOver {
+                winner: Some(Player::X),
+                games: vec![present(&state, 2, 0), present(&state, 3, 0)],
+            })
+        );
+        assert_eq!(state.winner(), Some(Player::X));
+        assert_eq!(
+            state.apply_move(present(&state, 4, 0).cell(0, 0)),
+            Err(MoveError::MatchOver)
+        );
+    }
+
+    #[test]
+    fn majority_victory() {
+        let mut state = row_of_five(Victory::Majority);
+        for column in [2, 0, 3] {
+            play(&mut state, column);
+        }
+        assert_eq!(play(&mut state, 1).match_over, None);
+        let outcome = play(&mut state, 4);
+        assert_eq!(
+            outcome.match_over,
+            Some(MatchOver {
+                winner: Some(Player::X),
+                games: vec![
+                    present(&state, 2, 0),
+                    present(&state, 3, 0),
+                    present(&state, 4, 0)
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn first_to_victory() {
+        let mut state = row_of_five(Victory::FirstTo(2));
+        play(&mut state, 2);
+        play(&mut state, 0);
+        let outcome = play(&mut state, 4);
+        assert_eq!(
+            outcome.match_over,
+            Some(MatchOver {
+                winner: Some(Player::X),
+                games: vec![present(&state, 2, 0), present(&state, 4, 0)],
+            })
+        );
+    }
+
+    #[test]
+    fn closed_games_give_a_free_choice() {
+        let mut state = GameState::new(rules(3, 1, 3, 1), Arc::new(Torus));
+        //The middle cell wins the game and sends O back to it.
+        let outcome = turn(&mut state, 1, 0, 0, 0);
+        assert_eq!(outcome.active_game, None);
+        assert_eq!(
+            state.active_games(),
+            vec![present(&state, 0, 0), present(&state, 2, 0)]
+        );
+        assert_eq!(
+            state.apply_move(present(&state, 1, 0).cell(1, 1)),
+            Err(MoveError::ClosedGame(present(&state, 1, 0)))
+        );
+        let outcome = turn(&mut state, 2, 0, 1, 0);
+        assert_eq!(outcome.active_game, Some(present(&state, 0, 0)));
+    }
+
+    ///Draws game 0 on a row of three games after X won game 1. The column of the played cell
+    /// picks the game of the next move.
+    fn draw_game(drawn_games: DrawnGames) -> (GameState, MoveOutcome) {
+        let rules = rules(3, 1, 3, 3)
+            .victory(Victory::InARow(2))
+            .drawn_games(drawn_games);
+        let mut state = GameState::new(rules, Arc::new(Classic));
+        let moves = [
+            (1, (-1, -1)),
+            (0, (0, 0)),
+            (1, (0, -1)),
+            (1, (-1, 1)),
+            (0, (-1, 1)),
+            (0, (0, 1)),
+            (1, (1, -1)),
+            (2, (-1, 0)),
+            (0, (1, 1)),
+            (2, (-1, 1)),
+            (0, (0, -1)),
+            (0, (1, 0)),
+            (2, (-1, -1)),
+            (0, (-1, -1)),
+        ];
+        for (column, (x, y)) in moves {
+            let outcome = turn(&mut state, column, 0, x, y);
+            assert_eq!(outcome.board_drawn, None);
+        }
+        assert_eq!(
+            state.board(present(&state, 1, 0)).unwrap().winner(),
+            Some(Player::X)
+        );
+        let outcome = turn(&mut state, 0, 0, -1, 0);
+        assert_eq!(
+            outcome.board_drawn,
+            Some(BoardDrawn {
+                game_id: present(&state, 0, 0)
+            })
+        );
+        (state, outcome)
+    }
+
+    #[test]
+    fn dead_draws_count_for_nobody() {
+        let (state, outcome) = draw_game(DrawnGames::Dead);
+        assert!(state.board(present(&state, 0, 0)).unwrap().is_drawn());
+        assert_eq!(outcome.match_over, None);
+
+        let mut state = GameState::new(rules(1, 1, 3, 3), Arc::new(Torus));
+        let moves = [(-1, 1), (0, 0), (1, 1), (0, 1), (0, -1), (1, 0), (-1, 0)];
+        for (x, y) in moves {
+            turn(&mut state, 0, 0, x, y);
+        }
+        let outcome = turn(&mut state, 0, 0, -1, -1);
+        assert_eq!(
+            outcome.match_over,
+            Some(MatchOver {
+                winner: None,
+                games: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn draws_counting_for_both_complete_a_line() {
+        let (state, outcome) = draw_game(DrawnGames::CountsForBoth);
+        assert!(state.board(present(&state, 0, 0)).unwrap().is_drawn());
+        assert_eq!(
+            outcome.match_over,
+            Some(MatchOver {
+                winner: Some(Player::X),
+                games: vec![present(&state, 0, 0), present(&state, 1, 0)],
+            })
+        );
+    }
+
+    #[test]
+    fn replayable_draws_are_cleared() {
+        let (state, outcome) = draw_game(DrawnGames::Replayable);
+        let board = state.board(present(&state, 0, 0)).unwrap();
+        assert!(!board.is_closed());
+        assert!((-1..=1).all(|x| (-1..=1).all(|y| board.get(x, y).is_none())));
+        assert_eq!(outcome.match_over, None);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_position() {
+        let mut state = branched();
+        let branch = Action::Move(game(0, 0).cell(-1, -1));
+        assert_eq!(state.actions().count(), 6);
+        assert_eq!(state.undo(), Some(Action::Submit));
+        assert_eq!(state.current_player(), Player::X);
+        assert_eq!(state.undo(), Some(branch));
+        assert_eq!(state.timeline_count(), 1);
+        assert_eq!(state.redo(), Some(Ok(branch)));
+        assert_eq!(state.parent(1), Some((0, 0)));
+        assert_eq!(state.redo(), Some(Ok(Action::Submit)));
+        assert_eq!(state.current_player(), Player::O);
+        assert!(!state.can_redo());
+
+        //Playing something else drops the undone actions.
+        state.undo();
+        state.undo();
+        state.apply_move(game(0, 0).cell(1, -1)).unwrap();
+        assert!(!state.can_redo());
+
+        let mut state = row_of_five(Victory::InARow(2));
+        for column in [2, 0, 3] {
+            play(&mut state, column);
+        }
+        assert!(state.is_over());
+        state.undo();
+        assert!(!state.is_over());
+        assert_eq!(state.winner(), None);
+    }
+
+    #[test]
+    fn a_drawn_timeline_leaves_the_match_open() {
+        let mut state = GameState::from_position(
+            rules(1, 1, 3, 3),
+            Arc::new(Torus),
+            "O L0T0:X@0,0=.../.../...;O@0,0=XOX/XOO/.X. L1^L0T0:O@0,0=X../.../...",
+        )
+        .unwrap();
+        let outcome = state.apply_move(game(0, 0).at(1, 0).cell(-1, -1)).unwrap();
+        assert!(outcome.board_drawn.is_some());
+        assert_eq!(outcome.match_over, None);
+        assert_eq!(state.obligations(), vec![game(0, 0).at(1, 1)]);
+    }
+
+    #[test]
+    fn reachable_games_include_the_past() {
+        let state = branched();
+        assert_eq!(state.active_games(), vec![game(0, 0).at(1, 1)]);
+        assert_eq!(
+            state.reachable_games(),
+            vec![game(0, 0).at(1, 0), game(0, 0).at(1, 1)]
+        );
+    }
+
+    #[test]
+    fn rules_that_can_not_be_played() {
+        assert_eq!(rules(3, 3, 3, 3).validate(), Ok(()));
+        for k in [0, 4] {
+            assert_eq!(rules(3, 3, 3, k).validate(), Err(InvalidRules::KOutOfRange));
+        }
+        let rules = rules(3, 1, 3, 3);
+        for (victory, error) in [
+            (Victory::InARow(0), InvalidRules::InARow),
+            (Victory::InARow(4), InvalidRules::InARow),
+            (Victory::FirstTo(4), InvalidRules::FirstTo),
+        ] {
+            assert_eq!(rules.clone().victory(victory).validate(), Err(error));
+        }
+    }
+}
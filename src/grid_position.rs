@@ -0,0 +1,117 @@
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+
+use crate::ActiveGames;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+///Position of a game on the board of games.
+///
+/// Axes, ordered from most to least significant:
+/// - timeline: index of the timeline the game lives on,
+/// - time: time step of the game on its timeline,
+/// - column: x coordinate on the board of games,
+/// - row: y coordinate on the board of games
+pub struct BoardPosition {
+    pub timeline: u32,
+    pub time: u32,
+    pub column: u32,
+    pub row: u32,
+}
+impl BoardPosition {
+    pub fn new(column: u32, row: u32) -> Self {
+        Self {
+            column,
+            row,
+            ..default()
+        }
+    }
+
+    pub fn at(mut self, time: u32, timeline: u32) -> Self {
+        self.time = time;
+        self.timeline = timeline;
+        self
+    }
+
+    ///Games are numbered column by column, so `id` sits at column `id / game_rows`.
+    pub fn from_game_id(id: u64, game_rows: u32) -> Self {
+        Self::new(
+            (id / game_rows as u64) as u32,
+            (id % game_rows as u64) as u32,
+        )
+    }
+
+    pub fn game_id(&self, game_rows: u32) -> u64 {
+        self.column as u64 * game_rows as u64 + self.row as u64
+    }
+
+    pub fn cell(&self, x: i16, y: i16) -> GridPosition {
+        GridPosition {
+            timeline: self.timeline,
+            time: self.time,
+            column: self.column,
+            row: self.row,
+            x,
+            y,
+        }
+    }
+}
+impl PartialEq<ActiveGames> for BoardPosition {
+    fn eq(&self, other: &ActiveGames) -> bool {
+        other.contains(self)
+    }
+}
+impl From<GridPosition> for BoardPosition {
+    fn from(value: GridPosition) -> Self {
+        value.board()
+    }
+}
+impl Debug for BoardPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[derive(Component, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+///Multidimensional position of a cell.
+///
+/// The axes of its [BoardPosition] followed by
+/// - x: x coordinate in a game,
+/// - y: y coordinate in a game
+pub struct GridPosition {
+    pub timeline: u32,
+    pub time: u32,
+    pub column: u32,
+    pub row: u32,
+    pub x: i16,
+    pub y: i16,
+}
+impl GridPosition {
+    pub fn new(x: i16, y: i16, board: BoardPosition) -> Self {
+        board.cell(x, y)
+    }
+
+    pub fn board(&self) -> BoardPosition {
+        BoardPosition {
+            timeline: self.timeline,
+            time: self.time,
+            column: self.column,
+            row: self.row,
+        }
+    }
+}
+impl PartialEq<ActiveGames> for GridPosition {
+    fn eq(&self, other: &ActiveGames) -> bool {
+        self.board() == *other
+    }
+}
+impl From<(i16, i16, BoardPosition)> for GridPosition {
+    fn from((x, y, board): (i16, i16, BoardPosition)) -> Self {
+        board.cell(x, y)
+    }
+}
+impl Debug for GridPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{self, BufRead};
+
+use crate::{Action, Engines, GameState};
+
+///Plays the match without a window.
+///
+/// Every line of stdin is an action in [notation](crate::notation), `undo` or `redo`,
+/// seats with an engine play on their own. Applied actions are printed, and once stdin ends or
+/// the match is decided, the result and the final position.
+pub fn run(mut state: GameState, engines: &Engines, auto_submit: bool) {
+    println!("{}", state.position());
+    let mut lines = io::stdin().lock().lines();
+    while !state.is_over() {
+        let player = state.current_player();
+        let (input, result) = if let Some(ai) = engines.get(player) {
+            let Some(action) = ai.choose(&state) else {
+                break;
+            };
+            (action.to_string(), play(&mut state, action))
+        } else {
+            let Some(Ok(line)) = lines.next() else {
+                break;
+            };
+            let result = match line.trim() {
+                "" => continue,
+                "undo" => {
+                    let undone =
+                        state.undo_turn(auto_submit, |player| engines.get(player).is_some());
+                    match undone {
+                        Some(action) => Ok(format!("undo {}", action)),
+                        None => Err("nothing to undo".to_string()),
+                    }
+                }
+                "redo" => match state.redo() {
+                    Some(Ok(action)) => Ok(format!("redo {}", action)),
+                    Some(Err(error)) => Err(error.to_string()),
+                    None => Err("nothing to redo".to_string()),
+                },
+                text => text
+                    .parse::<Action>()
+                    .map_err(|error| error.to_string())
+                    .and_then(|action| play(&mut state, action)),
+            };
+            (line.trim().to_string(), result)
+        };
+        match result {
+            Ok(action) => println!("{}: {}", player, action),
+            Err(error) => eprintln!("{}: {}", input, error),
+        }
+        if auto_submit && state.can_submit() && state.submit_turn().is_ok() {
+            println!("{}: {}", player, Action::Submit);
+        }
+    }
+    match state.result() {
+        Some(result) => match result.winner {
+            Some(winner) => println!("{} wins", winner),
+            None => println!("Draw"),
+        },
+        None => println!("Unfinished"),
+    }
+    println!("{}", state.position());
+}
+
+fn play(state: &mut GameState, action: Action) -> Result<String, String> {
+    match action {
+        Action::Move(pos) => state
+            .apply_move(pos)
+            .map(|outcome| Action::Move(outcome.position).to_string())
+            .map_err(|error| error.to_string()),
+        Action::Submit => state
+            .submit_turn()
+            .map(|_| Action::Submit.to_string())
+            .map_err(|error| error.to_string()),
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use stttwmdtt::{Action, Ai, BoardPosition, GameState, GridPosition, PlayerKind, Seats};
+
+use crate::{
+    ai_player::Analysis,
+    sttt::SuperTicTacToePlugin,
+    ttt::{GameSystems, PlayMode},
+};
+
+///What the hint overlay covers.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum HintPart {
+    Cell,
+    ///The game the opponent is sent to, drawn behind the games as a border.
+    Game,
+}
+
+///The suggested move and where it sends the opponent, `None` if they may choose any game.
+#[derive(Resource, Default)]
+struct Hint {
+    analysis: Option<Analysis>,
+    shown: Option<(GridPosition, Option<BoardPosition>)>,
+}
+
+#[derive(Resource)]
+struct HintEngine(Arc<dyn Ai>);
+
+fn init_hint(mut commands: Commands) {
+    for (part, z) in [(HintPart::Cell, 4.5), (HintPart::Game, -0.5)] {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ONE),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, z),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            part,
+        ));
+    }
+}
+
+///H asks for a hint on the turn of a human player, or hides the shown one.
+fn request_hint(
+    keys: Res<Input<KeyCode>>,
+    state: Res<GameState>,
+    seats: Res<Seats>,
+    engine: Res<HintEngine>,
+    mut hint: ResMut<Hint>,
+) {
+    if !keys.just_pressed(KeyCode::H) {
+        return;
+    }
+    if hint.shown.take().is_some() {
+        return;
+    }
+    if hint.analysis.is_some()
+        || state.is_over()
+        || seats.get(state.current_player()) != PlayerKind::Human
+    {
+        return;
+    }
+    info!("Looking for a hint for {}", state.current_player());
+    hint.analysis = Some(Analysis::start(engine.0.clone(), &state));
+}
+
+fn finish_hint(state: Res<GameState>, mut hint: ResMut<Hint>) {
+    if state.is_changed() {
+        hint.shown = None;
+    }
+    if !matches!(&hint.analysis, Some(analysis) if analysis.is_finished()) {
+        return;
+    }
+    let Some(analysis) = hint.analysis.take() else {
+        return;
+    };
+    match analysis.finish(&state) {
+        Some(Action::Move(pos)) => {
+            //Where the routing rule sends the opponent, on the time step the move creates, which
+            // lies on a new timeline for moves in the past.
+            let mut next = state.clone();
+            let sent_to = next
+                .apply_move(pos)
+                .ok()
+                .and_then(|outcome| outcome.active_game);
+            match sent_to {
+                Some(game) => info!("Hint: {}, sends the opponent to {}", pos, game),
+                None => info!("Hint: {}, lets the opponent choose the game", pos),
+            }
+            hint.shown = Some((pos, sent_to));
+        }
+        Some(Action::Submit) => info!("Hint: {}", Action::Submit),
+        None => {}
+    }
+}
+
+fn draw_hint(
+    layout: Res<SuperTicTacToePlugin>,
+    hint: Res<Hint>,
+    mut q_parts: Query<(&HintPart, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    if !hint.is_changed() && !layout.is_changed() {
+        return;
+    }
+    for (part, mut transform, mut sprite, mut visibility) in q_parts.iter_mut() {
+        let rect = match (part, hint.shown) {
+            (HintPart::Cell, Some((pos, _))) => Some(layout.cell_rect(pos)),
+            (HintPart::Game, Some((_, Some(game)))) => Some(layout.game_rect(game)),
+            _ => None,
+        };
+        *visibility = match rect {
+            Some(rect) => {
+                layout.draw_hint(rect, &mut transform, &mut sprite);
+                Visibility::Visible
+            }
+            None => Visibility::Hidden,
+        };
+    }
+}
+
+///Suggests a move for the player in front of the screen when they press H.
+pub struct HintPlugin {
+    engine: Arc<dyn Ai>,
+}
+impl HintPlugin {
+    pub fn new(engine: Arc<dyn Ai>) -> Self {
+        Self { engine }
+    }
+}
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HintEngine(self.engine.clone()))
+            .init_resource::<Hint>()
+            .init_resource::<Seats>()
+            .add_systems(Startup, init_hint)
+            .add_systems(
+                Update,
+                (request_hint.run_if(in_state(PlayMode::Play)), finish_hint)
+                    .chain()
+                    .in_set(GameSystems::Input),
+            )
+            .add_systems(Update, draw_hint.in_set(GameSystems::View));
+    }
+}
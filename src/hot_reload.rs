@@ -0,0 +1,94 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use bevy::prelude::*;
+use stttwmdtt::{config::Config, Rules};
+
+use crate::{sttt::SuperTicTacToePlugin, ttt::GameSystems};
+
+///How often the config file is checked for changes.
+const POLL_SECONDS: f32 = 0.5;
+
+#[derive(Resource)]
+struct ConfigWatcher {
+    path: PathBuf,
+    overrides: Vec<(&'static str, String)>,
+    timer: Timer,
+    modified: Option<SystemTime>,
+    ///Rules and routing rule of the last read config, to tell when they change.
+    rules: Option<(Rules, &'static str)>,
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+///Applies the sizes and colours of the config file whenever it is saved, the rules and the
+/// number and size of the games only change on a restart.
+fn reload_config(
+    time: Res<Time>,
+    mut watcher: ResMut<ConfigWatcher>,
+    mut layout: ResMut<SuperTicTacToePlugin>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified(&watcher.path);
+    if modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+    let config = match Config::load(&watcher.path, &watcher.overrides) {
+        Ok(config) => config,
+        Err(error) => {
+            error!("Could not reload {}: {}", watcher.path.display(), error);
+            return;
+        }
+    };
+    let rules = Some((config.rules, config.routing_rule.name()));
+    if watcher.rules != rules {
+        warn!(
+            "The rules in {} only change after a restart",
+            watcher.path.display()
+        );
+        watcher.rules = rules;
+    }
+    clear_color.0 = config.theme.background_color;
+    layout.apply_theme(config.theme);
+    info!("Reloaded the layout from {}", watcher.path.display());
+}
+
+///Watches the config file at `path` for new sizes and colours.
+pub struct HotReloadPlugin {
+    path: PathBuf,
+    overrides: Vec<(&'static str, String)>,
+}
+impl HotReloadPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            overrides: vec![],
+        }
+    }
+
+    ///Config keys set on the command line, which win over the file.
+    pub fn overrides(mut self, overrides: Vec<(&'static str, String)>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+}
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClearColor>()
+            .insert_resource(ConfigWatcher {
+                path: self.path.clone(),
+                overrides: self.overrides.clone(),
+                timer: Timer::from_seconds(POLL_SECONDS, TimerMode::Repeating),
+                modified: modified(&self.path),
+                rules: Config::load(&self.path, &self.overrides)
+                    .ok()
+                    .map(|config| (config.rules, config.routing_rule.name())),
+            })
+            .add_systems(Update, reload_config.before(GameSystems::Spawn));
+    }
+}
@@ -0,0 +1,128 @@
+//! `key value` lines, read by the config file and by saved matches.
+//!
+//! `#` starts a comment, empty lines are skipped and the key ends at the first whitespace.
+
+use std::{fmt::Display, str::FromStr};
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum EntryError {
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    DuplicateKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+///Entries from the command line have no line.
+pub(crate) fn location(line: usize) -> String {
+    match line {
+        0 => "command line".to_string(),
+        line => format!("line {}", line),
+    }
+}
+impl Display for EntryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryError::UnknownKey { line, key } => {
+                write!(f, "{}: unknown key `{}`", location(*line), key)
+            }
+            EntryError::DuplicateKey { line, key } => {
+                write!(f, "{}: `{}` is set twice", location(*line), key)
+            }
+            EntryError::InvalidValue {
+                line,
+                key,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{}: invalid value `{}` for `{}`, expected {}",
+                location(*line),
+                value,
+                key,
+                expected
+            ),
+        }
+    }
+}
+impl std::error::Error for EntryError {}
+
+///A `key value` line, line 0 stands for the command line.
+#[derive(Clone, Copy, Debug)]
+pub struct Entry<'a> {
+    pub line: usize,
+    pub key: &'a str,
+    pub value: &'a str,
+}
+impl<'a> Entry<'a> {
+    ///Splits `text` at its first whitespace.
+    pub fn new(line: usize, text: &'a str) -> Self {
+        let (key, value) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        Self {
+            line,
+            key,
+            value: value.trim(),
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self, expected: &'static str) -> Result<T, EntryError> {
+        self.value.parse().map_err(|_| self.invalid(expected))
+    }
+
+    pub fn positive<T: FromStr + PartialOrd + Default>(&self) -> Result<T, EntryError> {
+        positive(self.value).ok_or_else(|| self.invalid("a positive number"))
+    }
+
+    pub fn invalid(&self, expected: &'static str) -> EntryError {
+        EntryError::InvalidValue {
+            line: self.line,
+            key: self.key.to_string(),
+            value: self.value.to_string(),
+            expected,
+        }
+    }
+
+    pub fn unknown(&self) -> EntryError {
+        EntryError::UnknownKey {
+            line: self.line,
+            key: self.key.to_string(),
+        }
+    }
+}
+
+///Lines with text left after cutting off comments, numbered from 1.
+pub fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+///Adds `entry`, keys other than the `repeatable` ones may only be set once.
+pub fn push<'a>(
+    entries: &mut Vec<Entry<'a>>,
+    entry: Entry<'a>,
+    repeatable: &[&str],
+) -> Result<(), EntryError> {
+    if !repeatable.contains(&entry.key) && entries.iter().any(|e| e.key == entry.key) {
+        return Err(EntryError::DuplicateKey {
+            line: entry.line,
+            key: entry.key.to_string(),
+        });
+    }
+    entries.push(entry);
+    Ok(())
+}
+
+///A number above zero.
+pub fn positive<T: FromStr + PartialOrd + Default>(text: &str) -> Option<T> {
+    text.parse().ok().filter(|number| *number > T::default())
+}
@@ -1,4 +1,45 @@
-use bevy::prelude::*;
+use std::sync::Arc;
+
+use bevy::{prelude::*, utils::HashSet};
+
+mod ai;
+pub use ai::{Ai, AlphaBeta};
+mod mcts;
+pub use mcts::Mcts;
+
+mod grid_position;
+pub use grid_position::{BoardPosition, GridPosition};
+
+pub mod notation;
+pub use notation::NotationError;
+
+pub mod key_value;
+pub use key_value::EntryError;
+
+pub mod config;
+pub use config::{Config, ConfigError, Theme};
+
+pub mod cli;
+pub use cli::{Args, ArgsError, Frontend, StartError};
+
+pub mod headless;
+
+mod routing;
+pub use routing::{
+    routing_rule_by_name, Clamped, Classic, KleinBottle, MetaBoard, RoutingRule, Torus,
+};
+
+pub mod position;
+pub use position::PositionError;
+
+mod save;
+pub use save::{LoadError, SAVE_VERSION};
+
+mod game_state;
+pub use game_state::{
+    Action, Board, BoardDrawn, BoardWon, DrawnGames, GameState, InvalidRules, MatchOver, MoveError,
+    MoveOutcome, Player, Rules, UnknownValue, Victory,
+};
 
 #[derive(Resource)]
 pub struct CursorPosition(pub Vec2);
@@ -9,11 +50,91 @@ impl Default for CursorPosition {
     }
 }
 
-#[derive(Resource)]
-pub struct ActiveGame(pub u64);
+///Games the current player may play in, including past ones, classic play has exactly one.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ActiveGames(pub HashSet<BoardPosition>);
+impl ActiveGames {
+    pub fn single(game: BoardPosition) -> Self {
+        Self(HashSet::from_iter([game]))
+    }
 
-impl Default for ActiveGame {
-    fn default() -> Self {
-        Self(Default::default())
+    pub fn contains(&self, game: &BoardPosition) -> bool {
+        self.0.contains(game)
+    }
+}
+impl FromIterator<BoardPosition> for ActiveGames {
+    fn from_iter<T: IntoIterator<Item = BoardPosition>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
     }
 }
+
+///Whose turn it is, shown above the games.
+#[derive(Resource, Default)]
+pub struct CurrentPlayer(pub Player);
+
+///Who makes the moves of a player.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum PlayerKind {
+    #[default]
+    Human,
+    ///[AlphaBeta] search.
+    AlphaBeta,
+    ///[Mcts] search.
+    Mcts,
+}
+impl std::fmt::Display for PlayerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayerKind::Human => write!(f, "human"),
+            PlayerKind::AlphaBeta => write!(f, "alpha_beta"),
+            PlayerKind::Mcts => write!(f, "mcts"),
+        }
+    }
+}
+impl std::str::FromStr for PlayerKind {
+    type Err = UnknownValue;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(PlayerKind::Human),
+            "alpha_beta" => Ok(PlayerKind::AlphaBeta),
+            "mcts" => Ok(PlayerKind::Mcts),
+            _ => Err(UnknownValue(s.to_string())),
+        }
+    }
+}
+
+///Who plays X and who plays O.
+#[derive(Resource, Clone, Copy, Default, Debug)]
+pub struct Seats {
+    pub x: PlayerKind,
+    pub o: PlayerKind,
+}
+impl Seats {
+    pub fn get(&self, player: Player) -> PlayerKind {
+        match player {
+            Player::X => self.x,
+            Player::O => self.o,
+        }
+    }
+}
+
+///The computer players of both seats, `None` where a human plays.
+#[derive(Resource, Clone, Default)]
+pub struct Engines {
+    pub x: Option<Arc<dyn Ai>>,
+    pub o: Option<Arc<dyn Ai>>,
+}
+impl Engines {
+    pub fn get(&self, player: Player) -> Option<&Arc<dyn Ai>> {
+        match player {
+            Player::X => self.x.as_ref(),
+            Player::O => self.o.as_ref(),
+        }
+    }
+}
+
+///Games the current player has to play in before the turn can be submitted, shown next to the
+/// [CurrentPlayer].
+#[derive(Resource, Default)]
+pub struct TurnObligations(pub Vec<BoardPosition>);
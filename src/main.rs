@@ -1,32 +1,26 @@
-use bevy::prelude::*;
+use std::sync::Arc;
 
-const BACKGORUND_COLOR: Color = Color::Rgba {
-    red: 0.15,
-    green: 0.15,
-    blue: 0.15,
-    alpha: 1.0,
+use bevy::{prelude::*, window::WindowResolution};
+use stttwmdtt::{
+    cli::{fail, usage},
+    headless, Args, ArgsError, Frontend,
 };
 
 mod camera {
     use super::*;
-    use bevy::window::PrimaryWindow;
+    use bevy::{input::mouse::MouseWheel, window::PrimaryWindow};
     use stttwmdtt::CursorPosition;
 
+    use crate::sttt::TimeStepSpawned;
+
+    const PAN_SPEED: f32 = 800.0;
+    const ZOOM_STEP: f32 = 0.1;
+
     #[derive(Component)]
     struct MainCamera;
 
     pub fn init(mut commands: Commands) {
-        commands.spawn((
-            Camera2dBundle {
-                camera_2d: Camera2d {
-                    clear_color: bevy::core_pipeline::clear_color::ClearColorConfig::Custom(
-                        BACKGORUND_COLOR,
-                    ),
-                },
-                ..default()
-            },
-            MainCamera,
-        ));
+        commands.spawn((Camera2dBundle::default(), MainCamera));
     }
 
     fn set_cursor_position(
@@ -47,40 +41,135 @@ mod camera {
         }
     }
 
+    ///Centers the latest time step.
+    fn follow_present(
+        mut time_step_spawned: EventReader<TimeStepSpawned>,
+        mut q_camera: Query<&mut Transform, With<MainCamera>>,
+    ) {
+        if let Some(event) = time_step_spawned.read().last() {
+            let mut transform = q_camera.single_mut();
+            transform.translation.x = event.center.x;
+            transform.translation.y = event.center.y;
+        }
+    }
+
+    ///Arrow keys pan through the history, the mouse wheel zooms.
+    fn move_camera(
+        keys: Res<Input<KeyCode>>,
+        time: Res<Time>,
+        mut scroll: EventReader<MouseWheel>,
+        mut q_camera: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+    ) {
+        let (mut transform, mut projection) = q_camera.single_mut();
+        let mut direction = Vec2::ZERO;
+        for (key, step) in [
+            (KeyCode::Left, Vec2::NEG_X),
+            (KeyCode::Right, Vec2::X),
+            (KeyCode::Down, Vec2::NEG_Y),
+            (KeyCode::Up, Vec2::Y),
+        ] {
+            if keys.pressed(key) {
+                direction += step;
+            }
+        }
+        transform.translation +=
+            (direction * PAN_SPEED * projection.scale * time.delta_seconds()).extend(0.0);
+        for event in scroll.read() {
+            projection.scale = (projection.scale * (1.0 - event.y.signum() * ZOOM_STEP)).max(0.1);
+        }
+    }
+
     pub struct CameraPlugin;
     impl Plugin for CameraPlugin {
         fn build(&self, app: &mut App) {
             app.init_resource::<CursorPosition>()
                 .add_systems(Startup, init)
-                .add_systems(Update, set_cursor_position);
+                .add_event::<TimeStepSpawned>()
+                .add_systems(
+                    Update,
+                    (follow_present, move_camera, set_cursor_position).chain(),
+                );
         }
     }
 }
 
 mod active_game_listener;
+mod ai_player;
+mod game_over;
+mod hint;
+mod hot_reload;
+mod replay;
+mod save_load;
 mod sttt;
 mod ttt;
 
-const GAME_ROWS: u32 = 3;
-const GAMES_PER_ROW: u32 = 5;
+/// Pass the turn once every timeline on the present got a move, otherwise press return
+const AUTO_SUBMIT: bool = true;
+/// Ctrl+S saves the match here, Ctrl+O loads it
+const SAVE_FILE: &str = "match.sttt";
+/// Ctrl+E draws the match into this image, H suggests a move
+const SVG_FILE: &str = "match.svg";
 
 #[cfg(debug_assertions)]
 mod fps;
 
 fn main() {
+    let args = match Args::parse(std::env::args().skip(1), Frontend::Window) {
+        Ok(args) => args,
+        Err(help @ ArgsError::Help(_)) => {
+            println!("{}", help);
+            return;
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, usage(Frontend::Window));
+            std::process::exit(2);
+        }
+    };
+    let config = args.load_config().unwrap_or_else(|error| fail(error));
+    let state = args.game_state(&config).unwrap_or_else(|error| fail(error));
+    let engines = args.engines();
+    if args.headless {
+        headless::run(state, &engines, AUTO_SUBMIT);
+        return;
+    }
+    let layout = sttt::SuperTicTacToePlugin::default()
+        .theme(config.theme.clone())
+        .games_per_row(state.games_per_row())
+        .game_rows(state.game_rows())
+        .n(state.n());
+    if let Some(path) = &args.svg {
+        if let Err(error) = std::fs::write(path, layout.svg(&state)) {
+            fail(format!("Could not export to {}: {}", path.display(), error));
+        }
+        return;
+    }
+    let window = WindowPlugin {
+        primary_window: Some(Window {
+            resolution: args
+                .window
+                .map(|(width, height)| WindowResolution::new(width, height))
+                .unwrap_or_default(),
+            ..default()
+        }),
+        ..default()
+    };
     let mut app = App::new();
     let app = app
+        .insert_resource(ClearColor(config.theme.background_color))
+        .insert_resource(args.seats)
+        .add_plugins((DefaultPlugins.set(window), camera::CameraPlugin, layout))
         .add_plugins((
-            DefaultPlugins,
-            camera::CameraPlugin,
-            sttt::SuperTicTacToePlugin::default()
-                .game_rows(GAME_ROWS)
-                .games_per_row(GAMES_PER_ROW)
-                .background_color(BACKGORUND_COLOR),
+            ttt::MouseListenerPlugin,
+            ttt::ClickListener::new(state).auto_submit(AUTO_SUBMIT),
+            ai_player::AiPlayerPlugin::new(engines),
+            hint::HintPlugin::new(Arc::new(args.mcts.clone())),
         ))
-        .add_plugins((ttt::MouseListenerPlugin, ttt::ClickListener::new(GAMES_PER_ROW, GAME_ROWS)))
-        .add_plugins(active_game_listener::ActiveGameListenerPlugin::new(
-            GAMES_PER_ROW as u64 * GAME_ROWS as u64,
+        .add_plugins((
+            active_game_listener::ActiveGameListenerPlugin,
+            game_over::GameOverPlugin,
+            save_load::SaveLoadPlugin::new(SAVE_FILE).svg_path(SVG_FILE),
+            replay::ReplayPlugin::default(),
+            hot_reload::HotReloadPlugin::new(args.config).overrides(args.overrides),
         ));
     #[cfg(debug_assertions)]
     let app = app.add_plugins(fps::DiagnosticPlugin);
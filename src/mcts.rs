@@ -0,0 +1,224 @@
+use std::time::{Duration, Instant};
+
+use stttwmdtt_derive::Builder;
+
+use crate::{ai::evaluate, Action, Ai, GameState, GridPosition, Player};
+
+///Monte Carlo tree search with UCT selection.
+///
+/// Plays `iterations` random matches from the current position, each one starting with the
+/// moves that did best so far, and picks the move that was tried most often. `time_limit` stops
+/// the search earlier. Random matches that run longer than `playout_moves` are scored by who is
+/// ahead. With the same `seed` and without a time limit the same position always gets the same
+/// answer.
+#[derive(Builder, Clone, Debug)]
+pub struct Mcts {
+    iterations: u32,
+    time_limit: Option<Duration>,
+    ///Weight of rarely tried moves against well scoring ones.
+    exploration: f64,
+    seed: u64,
+    playout_moves: u32,
+}
+impl Default for Mcts {
+    fn default() -> Self {
+        Self {
+            iterations: 2_000,
+            time_limit: None,
+            exploration: std::f64::consts::SQRT_2,
+            seed: 0,
+            playout_moves: 60,
+        }
+    }
+}
+
+///SplitMix64, good enough for picking random moves and reproducible everywhere.
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+struct Node {
+    pos: Option<GridPosition>,
+    ///Who played `pos`.
+    player: Player,
+    children: Vec<usize>,
+    untried: Vec<GridPosition>,
+    visits: u32,
+    ///Won playouts of `player`, draws count half.
+    score: f64,
+}
+impl Node {
+    fn new(pos: Option<GridPosition>, player: Player, state: &GameState) -> Self {
+        Self {
+            pos,
+            player,
+            children: vec![],
+            untried: if state.is_over() {
+                vec![]
+            } else {
+                state.legal_moves()
+            },
+            visits: 0,
+            score: 0.0,
+        }
+    }
+}
+
+impl Mcts {
+    ///The child of `node` with the best upper confidence bound.
+    fn select(&self, nodes: &[Node], node: usize) -> usize {
+        let log_visits = (nodes[node].visits.max(1) as f64).ln();
+        let bound = |child: &Node| {
+            child.score / child.visits as f64
+                + self.exploration * (log_visits / child.visits as f64).sqrt()
+        };
+        nodes[node]
+            .children
+            .iter()
+            .copied()
+            .max_by(|a, b| bound(&nodes[*a]).total_cmp(&bound(&nodes[*b])))
+            .unwrap_or(node)
+    }
+
+    ///Plays random moves until the match is decided, returns the winner, `None` for a draw.
+    fn playout(&self, state: &mut GameState, rng: &mut Rng) -> Option<Player> {
+        let mut actions = 0;
+        for _ in 0..self.playout_moves {
+            if state.is_over() {
+                break;
+            }
+            let moves = state.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            match state.play(moves[rng.below(moves.len())]) {
+                Some(taken) => actions += taken,
+                None => break,
+            }
+        }
+        let winner = match state.result() {
+            Some(result) => result.winner,
+            None => match evaluate(state, Player::X).signum() {
+                1 => Some(Player::X),
+                -1 => Some(Player::O),
+                _ => None,
+            },
+        };
+        state.take_back(actions);
+        winner
+    }
+}
+impl Ai for Mcts {
+    fn choose(&self, state: &GameState) -> Option<Action> {
+        if state.is_over() {
+            return None;
+        }
+        if state.can_submit() {
+            return Some(Action::Submit);
+        }
+        let mut state = state.clone();
+        let root = Node::new(None, state.current_player().other(), &state);
+        match root.untried.len() {
+            0 => return None,
+            1 => return Some(Action::Move(root.untried[0])),
+            _ => {}
+        }
+        let mut nodes = vec![root];
+        let mut rng = Rng(self.seed);
+        let start = Instant::now();
+        for _ in 0..self.iterations {
+            if self
+                .time_limit
+                .is_some_and(|limit| start.elapsed() >= limit)
+            {
+                break;
+            }
+            let mut path = vec![0];
+            let mut actions = 0;
+            let mut node = 0;
+            while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+                node = self.select(&nodes, node);
+                let Some(pos) = nodes[node].pos else {
+                    break;
+                };
+                let Some(taken) = state.play(pos) else {
+                    break;
+                };
+                actions += taken;
+                path.push(node);
+            }
+            if !nodes[node].untried.is_empty() {
+                let untried = &mut nodes[node].untried;
+                let pos = untried.swap_remove(rng.below(untried.len()));
+                let player = state.current_player();
+                if let Some(taken) = state.play(pos) {
+                    actions += taken;
+                    nodes.push(Node::new(Some(pos), player, &state));
+                    let child = nodes.len() - 1;
+                    nodes[node].children.push(child);
+                    path.push(child);
+                }
+            }
+            let winner = self.playout(&mut state, &mut rng);
+            state.take_back(actions);
+            for node in path {
+                let node = &mut nodes[node];
+                node.visits += 1;
+                node.score += match winner {
+                    Some(winner) if winner == node.player => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+            }
+        }
+        nodes[0]
+            .children
+            .iter()
+            .max_by_key(|child| nodes[**child].visits)
+            .and_then(|child| nodes[*child].pos)
+            .map(Action::Move)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{routing_rule_by_name, Rules, Victory};
+
+    ///A few moves into a 3x3 match, with moves into the past on offer.
+    fn position() -> GameState {
+        let rules = Rules::default()
+            .games_per_row(3)
+            .game_rows(3)
+            .victory(Victory::InARow(3));
+        let mut state = GameState::new(rules, routing_rule_by_name("torus").unwrap());
+        for i in 0..6 {
+            let moves = state.legal_moves();
+            state.play(moves[i * 7 % moves.len()]).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn same_seed_same_choice() {
+        let state = position();
+        assert!(state.legal_moves().len() > 1);
+        let mcts = || Mcts::default().iterations(300).seed(7);
+        let first = mcts().choose(&state);
+        assert!(matches!(first, Some(Action::Move(_))));
+        for _ in 0..3 {
+            assert_eq!(mcts().choose(&state), first);
+        }
+    }
+}
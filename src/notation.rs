@@ -0,0 +1,311 @@
+//! Text notation for games, cells and actions.
+//!
+//! A game is written `L<timeline>T<time>/<column>,<row>` and a cell adds its centered
+//! coordinates in the game: `L<timeline>T<time>/<column>,<row>/<x>,<y>`.
+//! Columns and rows count from the bottom left game, x and y from the middle cell,
+//! so `L0T4/2,1/-1,0` is the cell left of the middle of the game in column 2 and row 1
+//! on time step 4 of the first timeline.
+//!
+//! An [Action] is either the cell of a move or `submit`.
+//!
+//! A [Board] is written row by row from the top, with `X`, `O` and `.` for empty cells and
+//! `/` between the rows, e.g. `X.O/.X./O..`.
+
+use std::{
+    fmt::Display,
+    str::{FromStr, Split},
+};
+
+use crate::{Action, Board, BoardPosition, GridPosition};
+
+const GAME_FORMAT: &str = "L<timeline>T<time>/<column>,<row>";
+const CELL_FORMAT: &str = "L<timeline>T<time>/<column>,<row>/<x>,<y>";
+const SUBMIT: &str = "submit";
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum NotationError {
+    ///`found` stands where `expected` should be, `format` is the full notation.
+    Expected {
+        expected: &'static str,
+        found: String,
+        format: &'static str,
+    },
+    ///`text` is no valid number for `field`.
+    InvalidNumber { field: &'static str, text: String },
+}
+impl Display for NotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotationError::Expected {
+                expected,
+                found,
+                format,
+            } => {
+                if found.is_empty() {
+                    write!(f, "expected {}, found nothing", expected)?;
+                } else {
+                    write!(f, "expected {}, found `{}`", expected, found)?;
+                }
+                write!(f, " (format is `{}`)", format)
+            }
+            NotationError::InvalidNumber { field, text } => {
+                write!(f, "`{}` is not a valid {}", text, field)
+            }
+        }
+    }
+}
+impl std::error::Error for NotationError {}
+
+impl Display for BoardPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "L{}T{}/{},{}",
+            self.timeline, self.time, self.column, self.row
+        )
+    }
+}
+impl FromStr for BoardPosition {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        let game = parse_game(&mut parts, GAME_FORMAT)?;
+        expect_end(parts, GAME_FORMAT)?;
+        Ok(game)
+    }
+}
+
+impl Display for GridPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{},{}", self.board(), self.x, self.y)
+    }
+}
+impl FromStr for GridPosition {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('/');
+        let game = parse_game(&mut parts, CELL_FORMAT)?;
+        let (x, y) = parse_pair(parts.next(), ("x", "y"), "the cell `<x>,<y>`", CELL_FORMAT)?;
+        expect_end(parts, CELL_FORMAT)?;
+        Ok(game.cell(x, y))
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Move(pos) => write!(f, "{}", pos),
+            Action::Submit => write!(f, "{}", SUBMIT),
+        }
+    }
+}
+impl FromStr for Action {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case(SUBMIT) {
+            return Ok(Action::Submit);
+        }
+        s.parse().map(Action::Move)
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let grid_origin = (self.n() as i16 - 1) / 2;
+        for row in 0..self.n() as i16 {
+            if row > 0 {
+                write!(f, "/")?;
+            }
+            let y = self.n() as i16 - 1 - row - grid_origin;
+            for x in -grid_origin..self.n() as i16 - grid_origin {
+                match self.get(x, y) {
+                    Some(player) => write!(f, "{}", player)?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_game(
+    parts: &mut Split<char>,
+    format: &'static str,
+) -> Result<BoardPosition, NotationError> {
+    let header = parts.next().unwrap_or_default();
+    let Some(header) = header.strip_prefix(['L', 'l']) else {
+        return Err(expected("`L` and the timeline", header, format));
+    };
+    let Some((timeline, time)) = header.split_once(['T', 't']) else {
+        return Err(expected("`T` and the time step", header, format));
+    };
+    let (column, row) = parse_pair(
+        parts.next(),
+        ("column", "row"),
+        "the game `<column>,<row>`",
+        format,
+    )?;
+    Ok(BoardPosition::new(column, row).at(
+        parse_number(time, "time step")?,
+        parse_number(timeline, "timeline")?,
+    ))
+}
+
+fn parse_pair<T: FromStr>(
+    part: Option<&str>,
+    (first, second): (&'static str, &'static str),
+    what: &'static str,
+    format: &'static str,
+) -> Result<(T, T), NotationError> {
+    let part = part.unwrap_or_default();
+    let Some((a, b)) = part.split_once(',') else {
+        return Err(expected(what, part, format));
+    };
+    Ok((parse_number(a, first)?, parse_number(b, second)?))
+}
+
+fn parse_number<T: FromStr>(text: &str, field: &'static str) -> Result<T, NotationError> {
+    text.trim()
+        .parse()
+        .map_err(|_| NotationError::InvalidNumber {
+            field,
+            text: text.to_string(),
+        })
+}
+
+fn expect_end(mut parts: Split<char>, format: &'static str) -> Result<(), NotationError> {
+    match parts.next() {
+        Some(rest) => Err(expected("the end", rest, format)),
+        None => Ok(()),
+    }
+}
+
+fn expected(expected: &'static str, found: &str, format: &'static str) -> NotationError {
+    NotationError::Expected {
+        expected,
+        found: found.to_string(),
+        format,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{GameState, Rules, Torus};
+
+    #[test]
+    fn positions_round_trip() {
+        let game = BoardPosition::new(2, 1).at(4, 3);
+        assert_eq!(game.to_string(), "L3T4/2,1");
+        assert_eq!(game.to_string().parse(), Ok(game));
+        for (x, y) in [(-1, 0), (0, -2), (-2, -1), (1, 2)] {
+            let cell = game.cell(x, y);
+            assert_eq!(cell.to_string().parse(), Ok(cell));
+        }
+        assert_eq!(
+            " l0t4/2,1/-1,0 ".parse(),
+            Ok(BoardPosition::new(2, 1).at(4, 0).cell(-1, 0))
+        );
+    }
+
+    #[test]
+    fn actions_round_trip() {
+        let cell = BoardPosition::new(0, 1).at(2, 1).cell(-1, 1);
+        for action in [Action::Move(cell), Action::Submit] {
+            assert_eq!(action.to_string().parse(), Ok(action));
+        }
+        assert_eq!(Action::Submit.to_string(), "submit");
+        assert_eq!("SUBMIT".parse(), Ok(Action::Submit));
+        assert_eq!(" Submit ".parse(), Ok(Action::Submit));
+    }
+
+    #[test]
+    fn board_rows_from_the_top() {
+        let rules = Rules::default().games_per_row(1).game_rows(1).n(3).k(3);
+        let mut state = GameState::new(rules, Arc::new(Torus));
+        let game = BoardPosition::new(0, 0);
+        for (time, (x, y)) in [(-1, 1), (1, 1), (0, 0), (0, -1)].into_iter().enumerate() {
+            state
+                .apply_move(game.at(time as u32, 0).cell(x, y))
+                .unwrap();
+            state.submit_turn().unwrap();
+        }
+        let board = state.board(game.at(4, 0)).unwrap();
+        assert_eq!(board.to_string(), "X.O/.X./.O.");
+        assert_eq!(Board::new(2).to_string(), "../..");
+    }
+
+    #[test]
+    fn missing_parts() {
+        assert_eq!(
+            "T0/0,0".parse::<BoardPosition>(),
+            Err(expected("`L` and the timeline", "T0", GAME_FORMAT))
+        );
+        assert_eq!(
+            "L0/0,0".parse::<BoardPosition>(),
+            Err(expected("`T` and the time step", "0", GAME_FORMAT))
+        );
+        assert_eq!(
+            "L0T0/00".parse::<BoardPosition>(),
+            Err(expected("the game `<column>,<row>`", "00", GAME_FORMAT))
+        );
+        assert_eq!(
+            "L0T0".parse::<BoardPosition>(),
+            Err(expected("the game `<column>,<row>`", "", GAME_FORMAT))
+        );
+        assert_eq!(
+            "L0T0/0,0/1-1".parse::<GridPosition>(),
+            Err(expected("the cell `<x>,<y>`", "1-1", CELL_FORMAT))
+        );
+        assert_eq!(
+            "sub".parse::<Action>(),
+            Err(expected("`L` and the timeline", "sub", CELL_FORMAT))
+        );
+    }
+
+    #[test]
+    fn trailing_parts() {
+        assert_eq!(
+            "L0T0/0,0/1,1".parse::<BoardPosition>(),
+            Err(expected("the end", "1,1", GAME_FORMAT))
+        );
+        assert_eq!(
+            "L0T0/0,0/1,1/".parse::<GridPosition>(),
+            Err(expected("the end", "", CELL_FORMAT))
+        );
+        assert_eq!(
+            "L0T0/0,0/1,1/2,2".parse::<Action>(),
+            Err(expected("the end", "2,2", CELL_FORMAT))
+        );
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        let invalid = |field, text: &str| NotationError::InvalidNumber {
+            field,
+            text: text.to_string(),
+        };
+        let game = |text: &str| text.parse::<BoardPosition>().unwrap_err();
+        let cell = |text: &str| text.parse::<GridPosition>().unwrap_err();
+        assert_eq!(game("LxT0/0,0"), invalid("timeline", "x"));
+        assert_eq!(game("L0T-1/0,0"), invalid("time step", "-1"));
+        assert_eq!(game("L0T0/a,0"), invalid("column", "a"));
+        assert_eq!(game("L0T0/0,"), invalid("row", ""));
+        assert_eq!(cell("L0T0/0,0/1.5,0"), invalid("x", "1.5"));
+        assert_eq!(cell("L0T0/0,0/0,y"), invalid("y", "y"));
+    }
+
+    #[test]
+    fn errors_show_the_format() {
+        let error = "L0T0".parse::<BoardPosition>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected the game `<column>,<row>`, found nothing (format is `L<timeline>T<time>/<column>,<row>`)"
+        );
+    }
+}
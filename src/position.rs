@@ -0,0 +1,415 @@
+//! One line position strings, like FEN for chess.
+//!
+//! A position is the side to move followed by every timeline, separated by spaces:
+//! `X L0T0:X@1,1=.../.../...,.../.../...`.
+//!
+//! A timeline starts with `L<timeline>T<start>`, or `L<timeline>^L<parent>T<time>` if it
+//! branched off from time step `time` of timeline `parent`, then a `:` and its time steps
+//! separated by `;`.
+//! A time step is the player to move, the game to play in as `@<column>,<row>` unless any open
+//! game may be chosen, a `=` and every [Board](crate::Board) in
+//! [notation](crate::notation), separated by `,` and ordered by [BoardPosition::game_id].
+
+use std::{fmt::Display, sync::Arc};
+
+use crate::{
+    game_state::{Snapshot, Timeline},
+    Board, BoardPosition, GameState, Player, RoutingRule, Rules,
+};
+
+const FORMAT: &str = "<side> L<timeline>T<start>:<player>@<column>,<row>=<board>,<board>;...";
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PositionError {
+    ///`found` stands where `expected` should be.
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+    InvalidNumber {
+        field: &'static str,
+        text: String,
+    },
+    ///Timelines have to be listed in order.
+    TimelineOrder {
+        expected: u32,
+        found: String,
+    },
+    ///The time step a timeline branched off from does not exist.
+    UnknownParent {
+        timeline: u32,
+        parent: String,
+    },
+    BoardCount {
+        time_step: String,
+        expected: usize,
+        found: usize,
+    },
+    ///A board is not n x n or has other cells than `X`, `O` and `.`.
+    InvalidBoard {
+        game: BoardPosition,
+        board: String,
+    },
+    ActiveGameOutOfBounds {
+        time_step: String,
+        column: u32,
+        row: u32,
+    },
+}
+impl Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::Expected { expected, found } => {
+                if found.is_empty() {
+                    write!(f, "expected {}, found nothing", expected)?;
+                } else {
+                    write!(f, "expected {}, found `{}`", expected, found)?;
+                }
+                write!(f, " (format is `{}`)", FORMAT)
+            }
+            PositionError::InvalidNumber { field, text } => {
+                write!(f, "`{}` is not a valid {}", text, field)
+            }
+            PositionError::TimelineOrder { expected, found } => {
+                write!(f, "expected timeline L{}, found `{}`", expected, found)
+            }
+            PositionError::UnknownParent { timeline, parent } => write!(
+                f,
+                "timeline L{} branches off from `{}`, which does not exist",
+                timeline, parent
+            ),
+            PositionError::BoardCount {
+                time_step,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} has {} boards, expected {}",
+                time_step, found, expected
+            ),
+            PositionError::InvalidBoard { game, board } => {
+                write!(f, "`{}` is no valid board for game {}", board, game)
+            }
+            PositionError::ActiveGameOutOfBounds {
+                time_step,
+                column,
+                row,
+            } => write!(
+                f,
+                "{} is sent to game {},{} which is not on the board",
+                time_step, column, row
+            ),
+        }
+    }
+}
+impl std::error::Error for PositionError {}
+
+impl GameState {
+    ///The position of every game in every time step, see [position](crate::position).
+    pub fn position(&self) -> String {
+        let mut text = self.current_player().to_string();
+        for (i, timeline) in self.timelines().iter().enumerate() {
+            text += &match timeline.parent {
+                Some((parent, time)) => format!(" L{}^L{}T{}:", i, parent, time),
+                None => format!(" L{}T{}:", i, timeline.start),
+            };
+            let time_steps: Vec<String> = timeline
+                .snapshots
+                .iter()
+                .map(|snapshot| {
+                    let active = snapshot
+                        .active_game
+                        .map(|(column, row)| format!("@{},{}", column, row))
+                        .unwrap_or_default();
+                    let boards: Vec<String> =
+                        snapshot.boards.iter().map(Board::to_string).collect();
+                    format!("{}{}={}", snapshot.current_player, active, boards.join(","))
+                })
+                .collect();
+            text += &time_steps.join(";");
+        }
+        text
+    }
+
+    ///Sets up a match from a [GameState::position], the position has to fit the size of
+    /// `rules`.
+    pub fn from_position(
+        rules: Rules,
+        routing_rule: Arc<dyn RoutingRule>,
+        position: &str,
+    ) -> Result<Self, PositionError> {
+        let state = GameState::new(rules, routing_rule);
+        let (games_per_row, game_rows) = (state.games_per_row(), state.game_rows());
+        let mut fields = position.split_whitespace();
+        let side = fields.next().unwrap_or_default();
+        let current_player: Player = side
+            .parse()
+            .map_err(|_| expected("the side to move `X` or `O`", side))?;
+        let mut timelines: Vec<Timeline> = vec![];
+        for field in fields {
+            let index = timelines.len() as u32;
+            let Some((header, time_steps)) = field.split_once(':') else {
+                return Err(expected("`:` after the timeline", field));
+            };
+            let Some(header_rest) = header.strip_prefix('L') else {
+                return Err(expected("`L` and the timeline", header));
+            };
+            let (timeline, parent, start) = match header_rest.split_once('^') {
+                Some((timeline, parent)) => {
+                    let (parent_timeline, time) = parse_time_step(parent)?;
+                    let parent_exists =
+                        timelines
+                            .get(parent_timeline as usize)
+                            .is_some_and(|parent| {
+                                time.checked_sub(parent.start)
+                                    .is_some_and(|i| (i as usize) < parent.snapshots.len())
+                            });
+                    if !parent_exists {
+                        return Err(PositionError::UnknownParent {
+                            timeline: index,
+                            parent: parent.to_string(),
+                        });
+                    }
+                    let start = time_step_after(time, 1)?;
+                    (timeline, Some((parent_timeline, time)), start)
+                }
+                None => {
+                    let Some((timeline, start)) = header_rest.split_once('T') else {
+                        return Err(expected("`T` and the first time step", header));
+                    };
+                    (timeline, None, parse_number(start, "time step")?)
+                }
+            };
+            if timeline.parse() != Ok(index) {
+                return Err(PositionError::TimelineOrder {
+                    expected: index,
+                    found: header.to_string(),
+                });
+            }
+            let mut snapshots = vec![];
+            for (i, time_step) in time_steps.split(';').enumerate() {
+                let time = time_step_after(start, i)?;
+                //The next move on this time step needs a time step after it.
+                time_step_after(time, 1)?;
+                let game = |column, row| BoardPosition::new(column, row).at(time, index);
+                let name = format!("L{}T{}", index, time);
+                let Some((head, boards)) = time_step.split_once('=') else {
+                    return Err(expected("`=` before the boards", time_step));
+                };
+                let mut head = head.chars();
+                let player: Player = head
+                    .next()
+                    .and_then(|player| player.to_string().parse().ok())
+                    .ok_or_else(|| expected("the player to move", time_step))?;
+                let active_game = match head.as_str() {
+                    "" => None,
+                    active => {
+                        let Some((column, row)) =
+                            active.strip_prefix('@').and_then(|a| a.split_once(','))
+                        else {
+                            return Err(expected("`@<column>,<row>` or `=`", active));
+                        };
+                        let (column, row) =
+                            (parse_number(column, "column")?, parse_number(row, "row")?);
+                        if column >= games_per_row || row >= game_rows {
+                            return Err(PositionError::ActiveGameOutOfBounds {
+                                time_step: name,
+                                column,
+                                row,
+                            });
+                        }
+                        Some((column, row))
+                    }
+                };
+                let boards: Vec<&str> = boards.split(',').collect();
+                let games = games_per_row as usize * game_rows as usize;
+                if boards.len() != games {
+                    return Err(PositionError::BoardCount {
+                        time_step: name,
+                        expected: games,
+                        found: boards.len(),
+                    });
+                }
+                let boards = boards
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, board)| {
+                        let id = BoardPosition::from_game_id(id as u64, game_rows);
+                        parse_board(board, &state).ok_or_else(|| PositionError::InvalidBoard {
+                            game: game(id.column, id.row),
+                            board: board.to_string(),
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                snapshots.push(Snapshot {
+                    boards,
+                    active_game,
+                    current_player: player,
+                });
+            }
+            timelines.push(Timeline {
+                parent,
+                start,
+                snapshots,
+            });
+        }
+        if timelines.is_empty() {
+            return Err(expected("a timeline", ""));
+        }
+        Ok(state.with_timelines(current_player, timelines))
+    }
+}
+
+///Rows from the top, see [notation](crate::notation).
+fn parse_board(text: &str, state: &GameState) -> Option<Board> {
+    let n = state.n();
+    let rows: Vec<&str> = text.split('/').collect();
+    if rows.len() != n as usize || rows.iter().any(|row| row.chars().count() != n as usize) {
+        return None;
+    }
+    let mut cells = vec![None; n as usize * n as usize];
+    for (row, text) in rows.iter().enumerate() {
+        let y = n as usize - 1 - row;
+        for (x, cell) in text.chars().enumerate() {
+            cells[x * n as usize + y] = match cell {
+                '.' => None,
+                cell => Some(cell.to_string().parse().ok()?),
+            };
+        }
+    }
+    Some(Board::with_cells(n, state.k(), cells, state.drawn_games()))
+}
+
+///`L<timeline>T<time>`
+fn parse_time_step(text: &str) -> Result<(u32, u32), PositionError> {
+    let Some((timeline, time)) = text.strip_prefix('L').and_then(|text| text.split_once('T'))
+    else {
+        return Err(expected("`L<timeline>T<time>`", text));
+    };
+    Ok((
+        parse_number(timeline, "timeline")?,
+        parse_number(time, "time step")?,
+    ))
+}
+
+///The time step `steps` after `time`, which has to fit into a `u32`.
+fn time_step_after(time: u32, steps: usize) -> Result<u32, PositionError> {
+    u32::try_from(steps)
+        .ok()
+        .and_then(|steps| time.checked_add(steps))
+        .ok_or_else(|| PositionError::InvalidNumber {
+            field: "time step",
+            text: time.to_string(),
+        })
+}
+
+fn parse_number(text: &str, field: &'static str) -> Result<u32, PositionError> {
+    text.parse().map_err(|_| PositionError::InvalidNumber {
+        field,
+        text: text.to_string(),
+    })
+}
+
+fn expected(expected: &'static str, found: &str) -> PositionError {
+    PositionError::Expected {
+        expected,
+        found: found.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Torus;
+
+    fn rules() -> Rules {
+        Rules::default().games_per_row(1).game_rows(1).n(3).k(3)
+    }
+
+    fn from_position(position: &str) -> Result<GameState, PositionError> {
+        GameState::from_position(rules(), Arc::new(Torus), position)
+    }
+
+    #[test]
+    fn branched_positions_round_trip() {
+        let mut state = GameState::new(rules(), Arc::new(Torus));
+        let game = BoardPosition::new(0, 0);
+        for (time, (x, y)) in [(0, (0, 0)), (1, (1, 1)), (0, (-1, -1))] {
+            state.apply_move(game.at(time, 0).cell(x, y)).unwrap();
+            state.submit_turn().unwrap();
+        }
+        let position = state.position();
+        assert_eq!(
+            position,
+            "O L0T0:X@0,0=.../.../...;O@0,0=.../.X./...;X@0,0=..O/.X./... \
+             L1^L0T0:O@0,0=.../.../X.."
+        );
+        let loaded = from_position(&position).unwrap();
+        assert_eq!(loaded.position(), position);
+        assert_eq!(loaded.parent(1), Some((0, 0)));
+        assert_eq!((loaded.start(1), loaded.end(1)), (Some(1), Some(1)));
+        assert_eq!(loaded.current_player(), Player::O);
+    }
+
+    #[test]
+    fn time_steps_past_the_last_one() {
+        let error = PositionError::InvalidNumber {
+            field: "time step",
+            text: "4294967295".to_string(),
+        };
+        assert_eq!(
+            from_position("X L0T4294967295:X@0,0=.../.../...;O@0,0=X../.../...").err(),
+            Some(error.clone())
+        );
+        assert_eq!(
+            from_position("X L0T4294967294:X@0,0=.../.../...;O@0,0=X../.../...").err(),
+            Some(error)
+        );
+    }
+
+    #[test]
+    fn invalid_positions() {
+        let error = |position| from_position(position).err().map(|error| error.to_string());
+        assert_eq!(
+            from_position("X L1T0:X=.../.../...").err(),
+            Some(PositionError::TimelineOrder {
+                expected: 0,
+                found: "L1T0".to_string(),
+            })
+        );
+        assert_eq!(
+            from_position("X L0T0:X=.../.../... L1^L0T1:X=.../.../...").err(),
+            Some(PositionError::UnknownParent {
+                timeline: 1,
+                parent: "L0T1".to_string(),
+            })
+        );
+        assert_eq!(
+            from_position("X L0T0:X=.../.../...,.../.../...").err(),
+            Some(PositionError::BoardCount {
+                time_step: "L0T0".to_string(),
+                expected: 1,
+                found: 2,
+            })
+        );
+        assert_eq!(
+            from_position("X L0T2:X=.../.-./...").err(),
+            Some(PositionError::InvalidBoard {
+                game: BoardPosition::new(0, 0).at(2, 0),
+                board: ".../.-./...".to_string(),
+            })
+        );
+        assert_eq!(
+            from_position("X L0T0:X@1,0=.../.../...").err(),
+            Some(PositionError::ActiveGameOutOfBounds {
+                time_step: "L0T0".to_string(),
+                column: 1,
+                row: 0,
+            })
+        );
+        assert_eq!(
+            error("X L0T0:X@1,0=.../.../..."),
+            Some("L0T0 is sent to game 1,0 which is not on the board".to_string())
+        );
+    }
+}
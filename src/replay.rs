@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use stttwmdtt::{Action, GameState};
+use stttwmdtt_derive::Builder;
+
+use crate::ttt::{GameSystems, PlayMode};
+
+const DIGITS: [(KeyCode, KeyCode, char); 10] = [
+    (KeyCode::Key0, KeyCode::Numpad0, '0'),
+    (KeyCode::Key1, KeyCode::Numpad1, '1'),
+    (KeyCode::Key2, KeyCode::Numpad2, '2'),
+    (KeyCode::Key3, KeyCode::Numpad3, '3'),
+    (KeyCode::Key4, KeyCode::Numpad4, '4'),
+    (KeyCode::Key5, KeyCode::Numpad5, '5'),
+    (KeyCode::Key6, KeyCode::Numpad6, '6'),
+    (KeyCode::Key7, KeyCode::Numpad7, '7'),
+    (KeyCode::Key8, KeyCode::Numpad8, '8'),
+    (KeyCode::Key9, KeyCode::Numpad9, '9'),
+];
+
+#[derive(Resource)]
+struct Autoplay {
+    playing: bool,
+    moves_per_second: f32,
+    timer: Timer,
+}
+impl Autoplay {
+    fn set_speed(&mut self, moves_per_second: f32) {
+        self.moves_per_second = moves_per_second.clamp(0.25, 32.0);
+        self.timer
+            .set_duration(Duration::from_secs_f32(1.0 / self.moves_per_second));
+    }
+}
+
+///Move number typed in to jump to.
+#[derive(Resource, Default)]
+struct Seek(String);
+
+///The match as it was before the replay rewound it, put back once the replay ends.
+#[derive(Resource, Default)]
+struct Live(Option<GameState>);
+
+#[derive(Component)]
+struct ReplayText;
+
+fn moves(actions: impl Iterator<Item = Action>) -> usize {
+    actions
+        .filter(|action| matches!(action, Action::Move(_)))
+        .count()
+}
+
+///Plays the next move and the submit following it.
+fn step_forward(state: &mut GameState) -> bool {
+    if !matches!(state.undone_actions().next(), Some(Action::Move(_))) {
+        return false;
+    }
+    let _ = state.redo();
+    if state.undone_actions().next() == Some(Action::Submit) {
+        let _ = state.redo();
+    }
+    true
+}
+
+///Takes back the last move and the submits after it.
+fn step_back(state: &mut GameState) -> bool {
+    while let Some(action) = state.undo() {
+        if let Action::Move(_) = action {
+            return true;
+        }
+    }
+    false
+}
+
+fn seek(state: &mut GameState, move_number: usize) {
+    while moves(state.actions()) > move_number && step_back(state) {}
+    while moves(state.actions()) < move_number && step_forward(state) {}
+}
+
+fn init_replay_text(mut commands: Commands) {
+    commands.spawn((
+        ReplayText,
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            visibility: Visibility::Hidden,
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn toggle_replay(
+    keys: Res<Input<KeyCode>>,
+    mode: Res<State<PlayMode>>,
+    mut next_mode: ResMut<NextState<PlayMode>>,
+    mut autoplay: ResMut<Autoplay>,
+) {
+    if keys.just_pressed(KeyCode::R) {
+        next_mode.set(match mode.get() {
+            PlayMode::Play => PlayMode::Replay,
+            PlayMode::Replay => PlayMode::Play,
+        });
+        autoplay.playing = false;
+    }
+}
+
+fn start_replay(state: Res<GameState>, mut live: ResMut<Live>) {
+    live.0 = Some(state.clone());
+}
+
+fn end_replay(mut state: ResMut<GameState>, mut live: ResMut<Live>) {
+    if let Some(live) = live.0.take() {
+        *state = live;
+    }
+}
+
+fn control_replay(
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut state: ResMut<GameState>,
+    mut autoplay: ResMut<Autoplay>,
+    mut seek_input: ResMut<Seek>,
+) {
+    if keys.just_pressed(KeyCode::Comma) {
+        step_back(&mut state);
+    }
+    if keys.just_pressed(KeyCode::Period) {
+        step_forward(&mut state);
+    }
+    if keys.just_pressed(KeyCode::Home) {
+        seek(&mut state, 0);
+    }
+    if keys.just_pressed(KeyCode::End) {
+        seek(&mut state, usize::MAX);
+    }
+    if keys.just_pressed(KeyCode::Space) {
+        autoplay.playing = !autoplay.playing;
+        autoplay.timer.reset();
+    }
+    if keys.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        let speed = autoplay.moves_per_second * 2.0;
+        autoplay.set_speed(speed);
+    }
+    if keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        let speed = autoplay.moves_per_second / 2.0;
+        autoplay.set_speed(speed);
+    }
+    for (key, numpad_key, digit) in DIGITS {
+        if keys.any_just_pressed([key, numpad_key]) {
+            seek_input.0.push(digit);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        seek_input.0.pop();
+    }
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter]) {
+        if let Ok(move_number) = seek_input.0.parse() {
+            seek(&mut state, move_number);
+        }
+        seek_input.0.clear();
+    }
+    if autoplay.playing && autoplay.timer.tick(time.delta()).just_finished() {
+        autoplay.playing = step_forward(&mut state);
+    }
+}
+
+fn draw_replay_text(
+    state: Res<GameState>,
+    mode: Res<State<PlayMode>>,
+    autoplay: Res<Autoplay>,
+    seek_input: Res<Seek>,
+    mut q_text: Query<(&mut Text, &mut Visibility), With<ReplayText>>,
+) {
+    for (mut text, mut visibility) in q_text.iter_mut() {
+        if *mode.get() != PlayMode::Replay {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Visible;
+        let done = moves(state.actions());
+        text.sections[0].value = format!(
+            "Replay: move {}/{}, autoplay {} at {} moves/s {}\n\
+             , . step  Home End jump  0-9 Enter go to move  Space autoplay  + - speed  R play",
+            done,
+            done + moves(state.undone_actions()),
+            if autoplay.playing { "on" } else { "off" },
+            autoplay.moves_per_second,
+            if seek_input.0.is_empty() {
+                String::new()
+            } else {
+                format!("(go to move {})", seek_input.0)
+            },
+        );
+    }
+}
+
+///R switches between playing and watching the moves of the match again, leaving the replay
+/// goes back to where the match stood.
+#[derive(Builder)]
+pub struct ReplayPlugin {
+    moves_per_second: f32,
+}
+impl Default for ReplayPlugin {
+    fn default() -> Self {
+        Self {
+            moves_per_second: 2.0,
+        }
+    }
+}
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let mut autoplay = Autoplay {
+            playing: false,
+            moves_per_second: self.moves_per_second,
+            timer: Timer::default(),
+        };
+        autoplay.timer.set_mode(TimerMode::Repeating);
+        autoplay.set_speed(self.moves_per_second);
+        app.insert_resource(autoplay)
+            .init_resource::<Seek>()
+            .init_resource::<Live>()
+            .add_systems(Startup, init_replay_text)
+            .add_systems(OnEnter(PlayMode::Replay), start_replay)
+            .add_systems(OnExit(PlayMode::Replay), end_replay)
+            .add_systems(
+                Update,
+                (
+                    toggle_replay,
+                    control_replay.run_if(in_state(PlayMode::Replay)),
+                )
+                    .chain()
+                    .in_set(GameSystems::Input),
+            )
+            .add_systems(Update, draw_replay_text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use stttwmdtt::{BoardPosition, Rules, Torus};
+
+    use super::*;
+
+    #[test]
+    fn leaving_the_replay_keeps_every_move() {
+        let rules = Rules::default().games_per_row(1).game_rows(1).n(3).k(3);
+        let mut state = GameState::new(rules, Arc::new(Torus));
+        for (time, (x, y)) in [(0, 0), (1, 1), (-1, 1)].into_iter().enumerate() {
+            let game = BoardPosition::new(0, 0).at(time as u32, 0);
+            state.apply_move(game.cell(x, y)).unwrap();
+            state.submit_turn().unwrap();
+        }
+        let saved = state.save();
+        let mut world = World::new();
+        world.insert_resource(state);
+        world.init_resource::<Live>();
+
+        world.run_system_once(start_replay);
+        seek(&mut world.resource_mut::<GameState>(), 1);
+        assert_eq!(moves(world.resource::<GameState>().actions()), 1);
+        world.run_system_once(end_replay);
+        let state = world.resource::<GameState>();
+        assert_eq!(moves(state.actions()), 3);
+        assert_eq!(state.save(), saved);
+    }
+}
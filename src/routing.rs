@@ -0,0 +1,114 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::UnknownValue;
+
+///Decides which game the next player is sent to.
+///
+/// Games are given by their (column, row) on the meta board and cells by the
+/// centered coordinates of [GridPosition](crate::GridPosition).
+pub trait RoutingRule: Debug + Send + Sync {
+    fn route(&self, meta: MetaBoard, column: u32, row: u32, x: i16, y: i16) -> (u32, u32);
+
+    ///Name used in saved matches, see [routing_rule_by_name].
+    fn name(&self) -> &'static str;
+}
+
+///The built in rules by their [RoutingRule::name].
+pub fn routing_rule_by_name(name: &str) -> Result<Arc<dyn RoutingRule>, UnknownValue> {
+    match name {
+        "torus" => Ok(Arc::new(Torus)),
+        "clamped" => Ok(Arc::new(Clamped)),
+        "classic" => Ok(Arc::new(Classic)),
+        "klein_bottle" => Ok(Arc::new(KleinBottle)),
+        _ => Err(UnknownValue(name.to_string())),
+    }
+}
+
+///Size of the board of games and of the games themselves.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MetaBoard {
+    pub games_per_row: u32,
+    pub game_rows: u32,
+    pub n: u8,
+}
+
+///Moves the active game by the sign of the played cells offset, wrapping around like a torus.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Torus;
+impl RoutingRule for Torus {
+    fn route(&self, meta: MetaBoard, column: u32, row: u32, x: i16, y: i16) -> (u32, u32) {
+        (
+            wrap(column as i64 + x.signum() as i64, meta.games_per_row),
+            wrap(row as i64 + y.signum() as i64, meta.game_rows),
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        "torus"
+    }
+}
+
+///Like [Torus], but stops at the edges of the meta board.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Clamped;
+impl RoutingRule for Clamped {
+    fn route(&self, meta: MetaBoard, column: u32, row: u32, x: i16, y: i16) -> (u32, u32) {
+        (
+            clamp(column as i64 + x.signum() as i64, meta.games_per_row),
+            clamp(row as i64 + y.signum() as i64, meta.game_rows),
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        "clamped"
+    }
+}
+
+///Ultimate Tic-Tac-Toe: the played cell is the game the next player is sent to.
+///
+/// Meta boards that are not n x n wrap the cell coordinate around.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Classic;
+impl RoutingRule for Classic {
+    fn route(&self, meta: MetaBoard, _column: u32, _row: u32, x: i16, y: i16) -> (u32, u32) {
+        let grid_origin = (meta.n as i64 - 1) / 2;
+        (
+            wrap(x as i64 + grid_origin, meta.games_per_row),
+            wrap(y as i64 + grid_origin, meta.game_rows),
+        )
+    }
+
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+}
+
+///Like [Torus], but leaving over the top or bottom edge mirrors the column.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct KleinBottle;
+impl RoutingRule for KleinBottle {
+    fn route(&self, meta: MetaBoard, column: u32, row: u32, x: i16, y: i16) -> (u32, u32) {
+        let column = wrap(column as i64 + x.signum() as i64, meta.games_per_row);
+        let new_row = row as i64 + y.signum() as i64;
+        if new_row < 0 || new_row >= meta.game_rows as i64 {
+            (
+                meta.games_per_row - 1 - column,
+                wrap(new_row, meta.game_rows),
+            )
+        } else {
+            (column, new_row as u32)
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "klein_bottle"
+    }
+}
+
+fn wrap(value: i64, size: u32) -> u32 {
+    value.rem_euclid(size as i64) as u32
+}
+
+fn clamp(value: i64, size: u32) -> u32 {
+    value.clamp(0, size as i64 - 1) as u32
+}
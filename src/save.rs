@@ -0,0 +1,400 @@
+use std::{fmt::Display, fs, io, path::Path};
+
+use crate::{
+    key_value::{self, Entry},
+    routing_rule_by_name, Action, BoardPosition, EntryError, GameState, MoveError, NotationError,
+    Player, PositionError, Rules,
+};
+
+///Version written to saved matches, older ones can still be loaded.
+pub const SAVE_VERSION: u32 = 2;
+
+const REPEATED_KEYS: [&str; 2] = ["timeline", "board"];
+const KEYS: [&str; 11] = [
+    "version",
+    "games_per_row",
+    "game_rows",
+    "n",
+    "k",
+    "victory",
+    "drawn_games",
+    "routing_rule",
+    "current_player",
+    "position",
+    "moves",
+];
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    UnsupportedVersion(String),
+    MissingKey(&'static str),
+    Entry(EntryError),
+    Notation {
+        line: usize,
+        error: NotationError,
+    },
+    Position {
+        line: usize,
+        error: PositionError,
+    },
+    IllegalMove {
+        line: usize,
+        error: MoveError,
+    },
+    ///The replayed moves do not lead to the saved position.
+    Mismatch {
+        line: usize,
+        key: String,
+        saved: String,
+        replayed: String,
+    },
+}
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "{}", error),
+            LoadError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported version `{}`, expected 1 to {}",
+                version, SAVE_VERSION
+            ),
+            LoadError::MissingKey(key) => write!(f, "missing key `{}`", key),
+            LoadError::Entry(error) => write!(f, "{}", error),
+            LoadError::Notation { line, error } => write!(f, "line {}: {}", line, error),
+            LoadError::Position { line, error } => write!(f, "line {}: {}", line, error),
+            LoadError::IllegalMove { line, error } => write!(f, "line {}: {}", line, error),
+            LoadError::Mismatch {
+                line,
+                key,
+                saved,
+                replayed,
+            } => write!(
+                f,
+                "line {}: saved {} `{}` but the moves lead to `{}`",
+                line, key, saved, replayed
+            ),
+        }
+    }
+}
+impl std::error::Error for LoadError {}
+impl From<io::Error> for LoadError {
+    fn from(value: io::Error) -> Self {
+        LoadError::Io(value)
+    }
+}
+
+impl From<EntryError> for LoadError {
+    fn from(value: EntryError) -> Self {
+        LoadError::Entry(value)
+    }
+}
+
+fn mismatch(entry: &Entry, replayed: impl Display) -> LoadError {
+    LoadError::Mismatch {
+        line: entry.line,
+        key: entry.key.to_string(),
+        saved: entry.value.to_string(),
+        replayed: replayed.to_string(),
+    }
+}
+
+impl GameState {
+    ///Writes the match as `key value` lines followed by every action in [notation](crate::notation).
+    ///
+    /// Besides the rules and the actions, the file lists the side to move, the timelines and
+    /// the latest boards of every timeline, which are checked when loading. Matches that did
+    /// not start with empty boards also store the [position](crate::position) they started from.
+    pub fn save(&self) -> String {
+        let mut text = format!("# stttwmdtt match\nversion {}\n", SAVE_VERSION);
+        text += &format!("games_per_row {}\n", self.games_per_row());
+        text += &format!("game_rows {}\n", self.game_rows());
+        text += &format!("n {}\n", self.n());
+        text += &format!("k {}\n", self.k());
+        text += &format!("victory {}\n", self.victory());
+        text += &format!("drawn_games {}\n", self.drawn_games());
+        text += &format!("routing_rule {}\n", self.routing_rule().name());
+        text += &format!("current_player {}\n", self.current_player());
+        if let Some(position) = self.start_position() {
+            text += &format!("position {}\n", position);
+        }
+        for timeline in 0..self.timeline_count() {
+            if let Some((parent, time)) = self.parent(timeline) {
+                text += &format!("timeline L{} L{}T{}\n", timeline, parent, time);
+            }
+        }
+        for game in self.games() {
+            if let Some(board) = self.board(game) {
+                text += &format!("board {} {}\n", game, board);
+            }
+        }
+        text += "moves\n";
+        for action in self.actions() {
+            text += &format!("{}\n", action);
+        }
+        text
+    }
+
+    ///Replays the actions of a saved match, files of older versions are read as well.
+    pub fn load(text: &str) -> Result<Self, LoadError> {
+        let mut entries: Vec<Entry> = vec![];
+        let mut lines = key_value::lines(text);
+        for (line, text) in lines.by_ref() {
+            let entry = Entry::new(line, text);
+            if !KEYS.contains(&entry.key) && !REPEATED_KEYS.contains(&entry.key) {
+                return Err(entry.unknown().into());
+            }
+            key_value::push(&mut entries, entry, &REPEATED_KEYS)?;
+            if entry.key == "moves" {
+                break;
+            }
+        }
+        let get = |key: &'static str| {
+            entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .ok_or(LoadError::MissingKey(key))
+        };
+        let version = get("version")?;
+        let supported = |version: u32| (1..=SAVE_VERSION).contains(&version);
+        if !version.value.parse().is_ok_and(supported) {
+            return Err(LoadError::UnsupportedVersion(version.value.to_string()));
+        }
+        let rules = Rules::default()
+            .games_per_row(get("games_per_row")?.positive()?)
+            .game_rows(get("game_rows")?.positive()?)
+            .n(get("n")?.positive()?)
+            .k(get("k")?.positive()?)
+            .victory(get("victory")?.parse("`in_a_row <count>`, `majority` or `first_to <count>`")?)
+            .drawn_games(get("drawn_games")?.parse("`dead`, `counts_for_both` or `replayable`")?);
+        if let Err(error) = rules.validate() {
+            return Err(get(error.key())?.invalid(error.expected()).into());
+        }
+        let routing_entry = get("routing_rule")?;
+        let routing_rule = routing_rule_by_name(routing_entry.value).map_err(|_| {
+            routing_entry.invalid("`torus`, `clamped`, `classic` or `klein_bottle`")
+        })?;
+        get("moves")?;
+
+        let mut state = match entries.iter().find(|entry| entry.key == "position") {
+            Some(entry) => {
+                GameState::from_position(rules, routing_rule, entry.value).map_err(|error| {
+                    LoadError::Position {
+                        line: entry.line,
+                        error,
+                    }
+                })?
+            }
+            None => GameState::new(rules, routing_rule),
+        };
+        for (line, text) in lines {
+            let action: Action = text
+                .parse()
+                .map_err(|error| LoadError::Notation { line, error })?;
+            let result = match action {
+                Action::Move(pos) => state.apply_move(pos).map(|_| ()),
+                Action::Submit => state.submit_turn().map(|_| ()),
+            };
+            result.map_err(|error| LoadError::IllegalMove { line, error })?;
+        }
+
+        let current_player = get("current_player")?;
+        if current_player.parse::<Player>("`X` or `O`")? != state.current_player() {
+            return Err(mismatch(current_player, state.current_player()));
+        }
+        let timelines: Vec<_> = entries.iter().filter(|e| e.key == "timeline").collect();
+        for entry in &timelines {
+            let (timeline, parent) = entry.value.split_once(' ').unwrap_or((entry.value, ""));
+            let replayed = timeline
+                .strip_prefix('L')
+                .and_then(|timeline| timeline.parse().ok())
+                .and_then(|timeline| state.parent(timeline));
+            let Some((parent_timeline, time)) = replayed else {
+                return Err(mismatch(entry, "no such timeline"));
+            };
+            let replayed = format!("L{}T{}", parent_timeline, time);
+            if parent.trim() != replayed {
+                return Err(mismatch(entry, format!("{} {}", timeline, replayed)));
+            }
+        }
+        if timelines.len() as u32 + 1 != state.timeline_count() {
+            return Err(LoadError::Mismatch {
+                line: get("moves")?.line,
+                key: "timeline count".to_string(),
+                saved: (timelines.len() + 1).to_string(),
+                replayed: state.timeline_count().to_string(),
+            });
+        }
+        for entry in entries.iter().filter(|e| e.key == "board") {
+            let (game, board) = entry.value.split_once(' ').unwrap_or((entry.value, ""));
+            let game: BoardPosition = game.parse().map_err(|error| LoadError::Notation {
+                line: entry.line,
+                error,
+            })?;
+            let replayed = state
+                .board(game)
+                .map(|board| board.to_string())
+                .unwrap_or_else(|| "no such game".to_string());
+            if board.trim() != replayed {
+                return Err(mismatch(entry, format!("{} {}", game, replayed)));
+            }
+        }
+        Ok(state)
+    }
+
+    pub fn save_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.save())
+    }
+
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        Self::load(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{InvalidRules, Torus};
+
+    const START: &str = "X L0T0:X@0,0=.../.O./...";
+
+    ///A match from [START] with a second timeline, and its save.
+    fn saved() -> (GameState, String) {
+        let rules = Rules::default().games_per_row(1).game_rows(1).n(3).k(3);
+        let mut state = GameState::from_position(rules, Arc::new(Torus), START).unwrap();
+        let game = BoardPosition::new(0, 0);
+        for (time, (x, y)) in [(0, (1, 1)), (1, (-1, -1)), (0, (-1, 1))] {
+            state.apply_move(game.at(time, 0).cell(x, y)).unwrap();
+            state.submit_turn().unwrap();
+        }
+        let text = state.save();
+        (state, text)
+    }
+
+    ///Number of the first line starting with `prefix`.
+    fn line_of(text: &str, prefix: &str) -> usize {
+        text.lines()
+            .position(|line| line.starts_with(prefix))
+            .unwrap()
+            + 1
+    }
+
+    fn load_error(text: &str) -> String {
+        GameState::load(text).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn branched_matches_round_trip() {
+        let (state, text) = saved();
+        assert!(text.contains(&format!("position {}\n", START)));
+        assert!(text.contains("timeline L1 L0T0\n"));
+        let loaded = GameState::load(&text).unwrap();
+        assert_eq!(loaded.position(), state.position());
+        assert_eq!(loaded.start_position(), Some(START));
+        assert_eq!(loaded.actions().count(), 6);
+        assert_eq!(loaded.save(), text);
+    }
+
+    #[test]
+    fn unsupported_versions() {
+        let (_, text) = saved();
+        let text = text.replace("version 2", "version 3");
+        assert!(matches!(
+            GameState::load(&text),
+            Err(LoadError::UnsupportedVersion(version)) if version == "3"
+        ));
+        assert_eq!(
+            load_error(&text),
+            "unsupported version `3`, expected 1 to 2"
+        );
+        let (_, text) = saved();
+        assert!(GameState::load(&text.replace("version 2", "version 1")).is_ok());
+        assert!(matches!(
+            GameState::load_file("no/such/match.sttt"),
+            Err(LoadError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let (_, text) = saved();
+        assert!(matches!(
+            GameState::load(&text.replace("routing_rule torus\n", "")),
+            Err(LoadError::MissingKey("routing_rule"))
+        ));
+        let unknown = text.replace("n 3\n", "size 3\n");
+        assert_eq!(
+            load_error(&unknown),
+            format!("line {}: unknown key `size`", line_of(&text, "n "))
+        );
+        let k = line_of(&text, "k ");
+        let above_n = text.replace("k 3\n", "k 4\n");
+        assert!(matches!(
+            GameState::load(&above_n),
+            Err(LoadError::Entry(EntryError::InvalidValue { line, .. })) if line == k
+        ));
+        assert_eq!(
+            load_error(&above_n),
+            format!(
+                "line {}: invalid value `4` for `k`, expected {}",
+                k,
+                InvalidRules::KOutOfRange.expected()
+            )
+        );
+    }
+
+    #[test]
+    fn rules_that_do_not_fit_the_match() {
+        let (_, text) = saved();
+        let position = line_of(&text, "position ");
+        let larger = text.replace("n 3\n", "n 4\n");
+        assert!(matches!(
+            GameState::load(&larger),
+            Err(LoadError::Position { line, error: PositionError::InvalidBoard { .. } })
+                if line == position
+        ));
+        assert_eq!(
+            load_error(&larger),
+            format!(
+                "line {}: `.../.O./...` is no valid board for game L0T0/0,0",
+                position
+            )
+        );
+        let player = line_of(&text, "current_player ");
+        let changed = text.replace("current_player O\n", "current_player X\n");
+        assert!(matches!(
+            GameState::load(&changed),
+            Err(LoadError::Mismatch { line, .. }) if line == player
+        ));
+        assert_eq!(
+            load_error(&changed),
+            format!(
+                "line {}: saved current_player `X` but the moves lead to `O`",
+                player
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let (_, text) = saved();
+        let first_move = line_of(&text, "moves") + 1;
+        let occupied = text.replace("moves\nL0T0/0,0/1,1\n", "moves\nL0T0/0,0/0,0\n");
+        assert!(matches!(
+            GameState::load(&occupied),
+            Err(LoadError::IllegalMove { line, error: MoveError::Occupied(_) })
+                if line == first_move
+        ));
+        assert_eq!(
+            load_error(&occupied),
+            format!("line {}: L0T0/0,0/0,0 is already marked", first_move)
+        );
+        let unreadable = text.replace("moves\nL0T0/0,0/1,1\n", "moves\nL0T0/0,0/1;1\n");
+        assert!(matches!(
+            GameState::load(&unreadable),
+            Err(LoadError::Notation { line, .. }) if line == first_move
+        ));
+    }
+}
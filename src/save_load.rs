@@ -0,0 +1,134 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use stttwmdtt::{ActiveGames, GameState};
+
+use crate::{
+    sttt::SuperTicTacToePlugin,
+    ttt::{GameId, GameSystems, PlayMode},
+};
+
+#[derive(Resource, Clone)]
+struct SavePath(PathBuf);
+
+#[derive(Resource, Clone)]
+struct SvgPath(PathBuf);
+
+fn save_match(keys: Res<Input<KeyCode>>, path: Res<SavePath>, state: Res<GameState>) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::S)
+    {
+        return;
+    }
+    match state.save_file(&path.0) {
+        Ok(()) => info!("Saved the match to {}", path.0.display()),
+        Err(error) => error!("Could not save to {}: {}", path.0.display(), error),
+    }
+}
+
+fn export_svg(
+    keys: Res<Input<KeyCode>>,
+    path: Res<SvgPath>,
+    state: Res<GameState>,
+    layout: Res<SuperTicTacToePlugin>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::E)
+    {
+        return;
+    }
+    match fs::write(&path.0, layout.svg(&state)) {
+        Ok(()) => info!("Exported the match to {}", path.0.display()),
+        Err(error) => error!("Could not export to {}: {}", path.0.display(), error),
+    }
+}
+
+fn log_position(keys: Res<Input<KeyCode>>, state: Res<GameState>) {
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        && keys.just_pressed(KeyCode::P)
+    {
+        info!("Position: {}", state.position());
+    }
+}
+
+///Replaces the match and respawns every game, the layout only fits matches of the same size.
+fn load_match(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    path: Res<SavePath>,
+    mut state: ResMut<GameState>,
+    mut active_games: ResMut<ActiveGames>,
+    q_games: Query<Entity, With<GameId>>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::O)
+    {
+        return;
+    }
+    let loaded = match GameState::load_file(&path.0) {
+        Ok(loaded) => loaded,
+        Err(error) => {
+            error!("Could not load {}: {}", path.0.display(), error);
+            return;
+        }
+    };
+    if (loaded.games_per_row(), loaded.game_rows(), loaded.n())
+        != (state.games_per_row(), state.game_rows(), state.n())
+    {
+        error!(
+            "Could not load {}: the match is played on {}x{} games of size {}",
+            path.0.display(),
+            loaded.games_per_row(),
+            loaded.game_rows(),
+            loaded.n()
+        );
+        return;
+    }
+    for entity in q_games.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    active_games.0.clear();
+    *state = loaded;
+    info!("Loaded the match from {}", path.0.display());
+}
+
+///Ctrl+S saves the match to `path`, Ctrl+O loads it back and Ctrl+P logs its position.
+/// Saving and loading wait until a replay is over.
+///
+/// Ctrl+E draws it into an SVG image at `svg_path`.
+pub struct SaveLoadPlugin {
+    path: PathBuf,
+    svg_path: PathBuf,
+}
+impl SaveLoadPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            svg_path: path.with_extension("svg"),
+            path,
+        }
+    }
+
+    pub fn svg_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.svg_path = path.into();
+        self
+    }
+}
+impl Plugin for SaveLoadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveGames>()
+            .insert_resource(SavePath(self.path.clone()))
+            .insert_resource(SvgPath(self.svg_path.clone()))
+            .add_systems(
+                Update,
+                (
+                    save_match.run_if(in_state(PlayMode::Play)),
+                    export_svg,
+                    log_position,
+                    load_match.run_if(in_state(PlayMode::Play)),
+                )
+                    .chain()
+                    .in_set(GameSystems::Input),
+            );
+    }
+}
@@ -1,114 +1,285 @@
-use bevy::prelude::*;
-use stttwmdtt::ActiveGame;
+use bevy::{prelude::*, utils::HashSet};
+use stttwmdtt::{config::Theme, ActiveGames, BoardPosition, GameState, GridPosition};
 use stttwmdtt_derive::Builder;
 
-use crate::ttt::TicTacToePlugin;
+use crate::ttt::{
+    svg_rect, GameAssets, GameId, GameStyle, GameSystems, PartHandles, TicTacToePlugin,
+};
 
-#[derive(Builder)]
+///All games of a time step have been spawned around `center`.
+#[derive(Event, Clone, Debug)]
+pub struct TimeStepSpawned {
+    pub center: Vec2,
+}
+
+///Band behind the time steps of the present.
+#[derive(Component)]
+struct PresentLine;
+
+///Also the resource holding the current layout, see [SuperTicTacToePlugin::apply_theme].
+#[derive(Builder, Resource, Clone)]
 pub struct SuperTicTacToePlugin {
     //MetaData
     games_per_row: u32,
     game_rows: u32,
     n: u8,
-    //Sizing
-    cell_size: f32,
-    cell_gap: f32,
-    game_padding: f32,
-    game_active_border_width: f32,
-    game_gap: f32,
-    //ActiveGameColors
-    cell_color: Color,
-    cell_hover_border_color: Color,
-    background_color: Color,
-    hover_background_color: Color,
-    game_active_border_color: Color,
-    //InactiveGameColors
-    inactive_cell_hover_border_color: Color,
-    inactive_hover_background_color: Color,
+    ///Sizes and colours, the only part that changes while playing.
+    theme: Theme,
 }
 impl SuperTicTacToePlugin {
+    ///Takes the sizes and colours of `theme`, the number and size of the games stay.
+    pub fn apply_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
     fn ttt_size(&self) -> f32 {
-        let cell_width = self.cell_size + self.cell_gap;
-        let game_size = cell_width * self.n as f32 - self.cell_gap;
-        let game_hover_size = game_size + 2.0 * self.game_padding;
-        let game_highlight_size = game_hover_size + 2.0 * self.game_active_border_width;
-        game_highlight_size + self.game_gap
+        let cell_width = self.theme.cell_size + self.theme.cell_gap;
+        let game_size = cell_width * self.n as f32 - self.theme.cell_gap;
+        let game_hover_size = game_size + 2.0 * self.theme.game_padding;
+        let game_highlight_size = game_hover_size + 2.0 * self.theme.game_active_border_width;
+        game_highlight_size + self.theme.game_gap
+    }
+
+    fn time_step_size(&self) -> Vec2 {
+        Vec2::new(
+            self.games_per_row as f32 * self.ttt_size() - self.theme.game_gap,
+            self.game_rows as f32 * self.ttt_size() - self.theme.game_gap,
+        )
+    }
+
+    ///Time steps are laid out from left to right, timelines from top to bottom.
+    fn time_step_center(&self, time: u32, timeline: u32) -> Vec2 {
+        let size = self.time_step_size() + self.theme.time_step_gap;
+        Vec2::new(time as f32 * size.x, -(timeline as f32) * size.y)
+    }
+
+    fn game_origin(&self, game: BoardPosition) -> Vec2 {
+        let ttt_size = self.ttt_size();
+        let width = self.games_per_row as f32 * ttt_size - ttt_size;
+        let x_offset = -width / 2.0;
+        let height = self.game_rows as f32 * ttt_size - ttt_size;
+        let y_offset = -height / 2.0;
+
+        self.time_step_center(game.time, game.timeline)
+            + Vec2::new(
+                game.column as f32 * ttt_size + x_offset,
+                game.row as f32 * ttt_size + y_offset,
+            )
+    }
+
+    fn game(&self, game: BoardPosition) -> TicTacToePlugin {
+        TicTacToePlugin::new(game, self.game_origin(game))
+            .n(self.n)
+            .cell_size(self.theme.cell_size)
+            .cell_gap(self.theme.cell_gap)
+            .game_padding(self.theme.game_padding)
+            .game_active_border_width(self.theme.game_active_border_width)
+            .cell_color(self.theme.cell_color)
+            .cell_hover_border_color(self.theme.cell_hover_border_color)
+            .background_color(self.theme.background_color)
+            .hover_background_color(self.theme.hover_background_color)
+            .game_active_border_color(self.theme.game_active_border_color)
+            .x_color(self.theme.x_color)
+            .o_color(self.theme.o_color)
+            .strike_color(self.theme.strike_color)
+            .inactive_cell_hover_border_color(self.theme.inactive_cell_hover_border_color)
+            .inactive_hover_background_color(self.theme.inactive_hover_background_color)
+    }
+
+    ///Center and size of the cell at `pos`.
+    pub fn cell_rect(&self, pos: GridPosition) -> (Vec2, Vec2) {
+        let grid_origin = (self.n as i16 - 1) / 2;
+        let center = self.game_origin(pos.board())
+            + self
+                .game(pos.board())
+                .cell_position(pos.x + grid_origin, pos.y + grid_origin);
+        (center, Vec2::splat(self.theme.cell_size))
+    }
+
+    ///Center and size of `game` with a border as wide as the one of active games around it.
+    pub fn game_rect(&self, game: BoardPosition) -> (Vec2, Vec2) {
+        let size =
+            self.ttt_size() - self.theme.game_gap + 2.0 * self.theme.game_active_border_width;
+        (self.game_origin(game), Vec2::splat(size))
+    }
+
+    ///Lays a hint over `rect`, see [SuperTicTacToePlugin::cell_rect] and
+    /// [SuperTicTacToePlugin::game_rect].
+    pub fn draw_hint(
+        &self,
+        (center, size): (Vec2, Vec2),
+        transform: &mut Transform,
+        sprite: &mut Sprite,
+    ) {
+        transform.translation = center.extend(transform.translation.z);
+        transform.scale = size.extend(1.0);
+        sprite.color = self.theme.hint_color;
+    }
+
+    ///Center and size of the band over the present of every timeline.
+    fn present_line(&self, state: &GameState) -> (Vec2, Vec2) {
+        let present = state.present();
+        let first = self.time_step_center(present, 0);
+        let last = self.time_step_center(present, state.timeline_count() - 1);
+        let size = self.time_step_size() + self.theme.time_step_gap / 2.0;
+        (
+            (first + last) / 2.0,
+            Vec2::new(size.x, size.y + first.y - last.y),
+        )
+    }
+
+    ///Stretches the [PresentLine] over the present of every timeline.
+    fn draw_present_line(&self, state: &GameState, transform: &mut Transform, sprite: &mut Sprite) {
+        let (center, size) = self.present_line(state);
+        transform.translation = center.extend(transform.translation.z);
+        transform.scale = size.extend(1.0);
+        sprite.color = self.theme.present_color;
+    }
+
+    ///Every time step of the match as an SVG image, laid out like the spawned games.
+    pub fn svg(&self, state: &GameState) -> String {
+        let margin = Vec2::splat(self.theme.time_step_gap / 2.0);
+        let (mut min, mut max) = (Vec2::MAX, Vec2::MIN);
+        for (timeline, time) in state.time_steps() {
+            let center = self.time_step_center(time, timeline);
+            min = min.min(center - self.time_step_size() / 2.0 - margin);
+            max = max.max(center + self.time_step_size() / 2.0 + margin);
+        }
+        let size = max - min;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+             viewBox=\"0 0 {0} {1}\">\n",
+            size.x, size.y
+        );
+        //World coordinates have y pointing up.
+        svg += &format!(
+            "<g transform=\"translate({} {}) scale(1 -1)\">\n",
+            -min.x, max.y
+        );
+        svg += &svg_rect((min + max) / 2.0, size, self.theme.background_color);
+        let (center, size) = self.present_line(state);
+        svg += &svg_rect(center, size, self.theme.present_color);
+        for (timeline, time) in state.time_steps() {
+            for game in state.games_at(time, timeline) {
+                svg += &self
+                    .game(game)
+                    .svg(state.board(game), state.is_reachable(game));
+            }
+        }
+        svg += "</g>\n</svg>\n";
+        svg
+    }
+
+    ///Spawns the games of every time step that is not on screen yet and despawns the games
+    /// of undone time steps.
+    fn spawn_time_steps(
+        &self,
+        state: &GameState,
+        q_games: &Query<(Entity, &GameId)>,
+        commands: &mut Commands,
+        assets: &mut GameAssets,
+        time_step_spawned: &mut EventWriter<TimeStepSpawned>,
+    ) {
+        let mut spawned = HashSet::new();
+        for (entity, id) in q_games.iter() {
+            if state.board(id.0).is_some() {
+                spawned.insert((id.0.timeline, id.0.time));
+            } else {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+        for (timeline, time) in state.time_steps() {
+            if spawned.contains(&(timeline, time)) {
+                continue;
+            }
+            for game in state.games_at(time, timeline) {
+                self.game(game).init(commands, assets);
+            }
+            time_step_spawned.send(TimeStepSpawned {
+                center: self.time_step_center(time, timeline),
+            });
+        }
     }
 }
+fn init_present_line(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::ONE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -1.0),
+            ..default()
+        },
+        PresentLine,
+    ));
+}
+
 impl Default for SuperTicTacToePlugin {
     fn default() -> Self {
         Self {
             games_per_row: 1,
             game_rows: 1,
             n: 3,
-            cell_size: 50.0,
-            cell_gap: 3.0,
-            cell_color: Color::WHITE,
-            cell_hover_border_color: Color::Rgba {
-                red: 0.3,
-                green: 0.8,
-                blue: 0.14,
-                alpha: 1.0,
-            },
-            game_padding: 15.0,
-            hover_background_color: Color::Rgba {
-                red: 0.2,
-                green: 0.28,
-                blue: 0.18,
-                alpha: 1.0,
-            },
-            game_active_border_width: 3.0,
-            game_active_border_color: Color::WHITE,
-            background_color: Color::BLACK,
-            inactive_cell_hover_border_color: Color::Rgba {
-                red: 0.9,
-                green: 0.0,
-                blue: 0.0,
-                alpha: 1.0,
-            },
-            inactive_hover_background_color: Color::Rgba {
-                red: 0.32,
-                green: 0.22,
-                blue: 0.24,
-                alpha: 1.0,
-            },
-            game_gap: 5.0,
+            theme: Theme::default(),
         }
     }
 }
 impl Plugin for SuperTicTacToePlugin {
-    fn build(&self, mut app: &mut App) {
-        let ttt_size = self.ttt_size();
-        let width = self.games_per_row as f32 * ttt_size - ttt_size;
-        let x_offset = -width / 2.0;
-        let height = self.game_rows as f32 * ttt_size - ttt_size;
-        let y_offset = -height / 2.0;
-
-        let mut id = 0;
-        for x in 0..self.games_per_row {
-            for y in 0..self.game_rows {
-                let origin = Vec2::new(
-                    x as f32 * ttt_size + x_offset,
-                    y as f32 * ttt_size + y_offset,
-                );
-                app = app.add_plugins(
-                    TicTacToePlugin::new(id, origin)
-                        .n(self.n)
-                        .cell_size(self.cell_size)
-                        .cell_gap(self.cell_gap)
-                        .game_padding(self.game_padding)
-                        .game_active_border_width(self.game_active_border_width)
-                        .cell_color(self.cell_color)
-                        .cell_hover_border_color(self.cell_hover_border_color)
-                        .background_color(self.background_color)
-                        .hover_background_color(self.hover_background_color)
-                        .game_active_border_color(self.game_active_border_color)
-                        .inactive_cell_hover_border_color(self.inactive_cell_hover_border_color)
-                        .inactive_hover_background_color(self.inactive_hover_background_color),
-                );
-                id += 1;
-            }
-        }
-        app.init_resource::<ActiveGame>();
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone())
+            .init_resource::<ActiveGames>()
+            .init_resource::<PartHandles>()
+            .add_event::<TimeStepSpawned>()
+            .add_systems(Startup, init_present_line)
+            .add_systems(
+                Update,
+                (|layout: Res<SuperTicTacToePlugin>,
+                  state: Res<GameState>,
+                  mut q_line: Query<(&mut Transform, &mut Sprite), With<PresentLine>>| {
+                    if !state.is_changed() && !layout.is_changed() {
+                        return;
+                    }
+                    for (mut transform, mut sprite) in q_line.iter_mut() {
+                        layout.draw_present_line(&state, &mut transform, &mut sprite);
+                    }
+                })
+                .in_set(GameSystems::View),
+            )
+            .add_systems(
+                Update,
+                (|layout: Res<SuperTicTacToePlugin>,
+                  state: Res<GameState>,
+                  q_games: Query<(Entity, &GameId)>,
+                  mut commands: Commands,
+                  mut assets: GameAssets,
+                  mut time_step_spawned: EventWriter<TimeStepSpawned>| {
+                    if !state.is_changed() {
+                        return;
+                    }
+                    layout.spawn_time_steps(
+                        &state,
+                        &q_games,
+                        &mut commands,
+                        &mut assets,
+                        &mut time_step_spawned,
+                    );
+                })
+                .in_set(GameSystems::Spawn),
+            )
+            .add_systems(
+                Update,
+                (|layout: Res<SuperTicTacToePlugin>,
+                  q_games: Query<(Entity, &GameId)>,
+                  mut style: GameStyle| {
+                    if !layout.is_changed() || layout.is_added() {
+                        return;
+                    }
+                    for (entity, id) in q_games.iter() {
+                        layout.game(id.0).restyle(entity, &mut style);
+                    }
+                })
+                .in_set(GameSystems::Spawn),
+            );
     }
 }
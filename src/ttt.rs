@@ -1,11 +1,14 @@
-use std::fmt::{Debug, Display};
-
-use bevy::{prelude::*, sprite::Material2d};
-use stttwmdtt::ActiveGame;
+use bevy::{
+    ecs::{query::Has, system::SystemParam},
+    prelude::*,
+    sprite::{Material2d, Mesh2dHandle},
+    utils::HashMap,
+};
+use stttwmdtt::{ActiveGames, BoardPosition, GridPosition};
 use stttwmdtt_derive::Builder;
 
 mod square;
-use square::{Cell, SquareBundle};
+use square::{Cell, SquareBundle, SquareSize, SquareType};
 
 mod mouse_listener;
 pub use mouse_listener::HoveredPosition;
@@ -13,56 +16,35 @@ pub use mouse_listener::MouseExitedCell;
 pub use mouse_listener::MouseExitedGame;
 pub use mouse_listener::MouseListenerPlugin;
 pub use mouse_listener::WrapperEvent;
-pub use square::{GameActive, Hover, SquareBuilder};
+pub use square::{GameActive, Hover, SquareBuilder, Strike};
 
 mod click_listener;
-pub use click_listener::ClickListener;
+pub use click_listener::{ClickListener, PlayMode};
+
+mod mark;
+use mark::{Glyph, Mark};
+
+mod svg;
+pub use svg::svg_rect;
 
 use self::square::Square;
 
-#[derive(Component, PartialEq, Clone)]
-///Multidimensional position of a cell.
-///
-/// Currently 3D:
-/// - x: x coordinate in a game,
-/// - y: y coordinate in a game,
-/// - id: id of the cells game
-pub struct GridPosition {
-    x: i16,
-    y: i16,
-    id: u64,
-}
-impl GridPosition {
-    fn new(x: i16, y: i16, game_id: u64) -> Self {
-        GridPosition { x, y, id: game_id }
-    }
-}
-impl PartialEq<ActiveGame> for GridPosition {
-    fn eq(&self, other: &ActiveGame) -> bool {
-        self.id == other.0
-    }
-}
-impl Default for GridPosition {
-    fn default() -> Self {
-        Self::new(0, 0, 0)
-    }
-}
-impl Display for GridPosition {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {}, {})", self.x, self.y, self.id)
-    }
-}
-impl Debug for GridPosition {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self)
-    }
+///Order of the game systems within a frame.
+#[derive(SystemSet, PartialEq, Eq, Hash, Clone, Debug)]
+pub enum GameSystems {
+    ///Moves are applied to the [GameState](stttwmdtt::GameState).
+    Input,
+    ///Games of new time steps are spawned.
+    Spawn,
+    ///Spawned games catch up with the [GameState](stttwmdtt::GameState).
+    View,
 }
 
 #[derive(Component, PartialEq, Clone, Debug)]
-pub struct GameId(pub u64);
-impl PartialEq<ActiveGame> for GameId {
-    fn eq(&self, other: &ActiveGame) -> bool {
-        self.0 == other.0
+pub struct GameId(pub BoardPosition);
+impl PartialEq<ActiveGames> for GameId {
+    fn eq(&self, other: &ActiveGames) -> bool {
+        self.0 == *other
     }
 }
 
@@ -70,12 +52,14 @@ impl PartialEq<ActiveGame> for GameId {
 struct CellBundle<M: Material2d> {
     square_bundle: SquareBundle<M, Cell>,
     grid_position: GridPosition,
+    mark: Mark,
 }
 impl<M: Material2d> Default for CellBundle<M> {
     fn default() -> Self {
         Self {
             square_bundle: default(),
             grid_position: default(),
+            mark: default(),
         }
     }
 }
@@ -99,7 +83,7 @@ impl<M: Material2d> Default for InactiveHoverBundle<M> {
 #[derive(Builder, Clone, Default)]
 pub struct TicTacToePlugin {
     //MetaData
-    game_id: u64,
+    game_id: BoardPosition,
     origin: Vec2,
     n: u8,
     //Sizing
@@ -113,12 +97,72 @@ pub struct TicTacToePlugin {
     background_color: Color,
     hover_background_color: Color,
     game_active_border_color: Color,
+    x_color: Color,
+    o_color: Color,
+    strike_color: Color,
     //InactiveColors
     inactive_cell_hover_border_color: Color,
     inactive_hover_background_color: Color,
 }
+///The squares a game is made of.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Part {
+    GameActive,
+    GameHover,
+    InactiveGameHover,
+    Background,
+    Strike,
+    Cell,
+    CellHover,
+    InactiveCellHover,
+}
+
+///Colour, drawn size and hovered size of a [Part].
+struct Look {
+    color: Color,
+    optical_size: f32,
+    size: f32,
+}
+
+///A square with what tells its [Part].
+type SquareParts = (
+    &'static mut Transform,
+    &'static mut SquareSize,
+    &'static Handle<ColorMaterial>,
+    &'static Mesh2dHandle,
+    Option<&'static GridPosition>,
+    Has<Cell>,
+    Has<Hover>,
+    Has<Inactive>,
+    Has<Strike>,
+);
+
+///One mesh and material per [Part], shared by the squares of every game.
+#[derive(Resource, Default)]
+pub struct PartHandles(HashMap<Part, (Handle<Mesh>, Handle<ColorMaterial>)>);
+
+///What [TicTacToePlugin::init] spawns games with.
+#[derive(SystemParam)]
+pub struct GameAssets<'w> {
+    parts: ResMut<'w, PartHandles>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+}
+
+///Everything [TicTacToePlugin::restyle] changes on spawned games.
+#[derive(SystemParam)]
+pub struct GameStyle<'w, 's> {
+    q_children: Query<'w, 's, &'static Children>,
+    q_parents: Query<'w, 's, &'static Parent>,
+    q_squares: Query<'w, 's, SquareParts>,
+    q_glyphs: Query<'w, 's, (&'static mut Glyph, &'static mut Text)>,
+    q_marks: Query<'w, 's, &'static mut Mark>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+}
+
 impl TicTacToePlugin {
-    pub fn new(id: u64, origin: Vec2) -> Self {
+    pub fn new(id: BoardPosition, origin: Vec2) -> Self {
         Self {
             game_id: id,
             origin,
@@ -126,35 +170,88 @@ impl TicTacToePlugin {
         }
     }
 
-    fn init(
-        &self,
-        active_game: Res<ActiveGame>,
-        mut commands: Commands,
-        mut meshes: ResMut<Assets<Mesh>>,
-        mut materials: ResMut<Assets<ColorMaterial>>,
-    ) {
+    fn look(&self, part: Part) -> Look {
         let cell_width = self.cell_size + self.cell_gap;
         let game_size = cell_width * self.n as f32 - self.cell_gap;
-        let cell_offset = -(game_size + self.cell_size + self.cell_gap * 2.0) / 4.0;
-
         let highlight_size = self.cell_size + 2.0 * self.cell_gap;
-
         let game_hover_size = game_size + 2.0 * self.game_padding;
         let game_highlight_size = game_hover_size + 2.0 * self.game_active_border_width;
 
+        let (color, optical_size, size) = match part {
+            Part::GameActive => (
+                self.game_active_border_color,
+                game_highlight_size,
+                game_highlight_size,
+            ),
+            Part::GameHover => (
+                self.hover_background_color,
+                game_hover_size,
+                game_hover_size,
+            ),
+            Part::InactiveGameHover => (
+                self.inactive_hover_background_color,
+                game_hover_size,
+                game_hover_size,
+            ),
+            Part::Background => (self.background_color, game_hover_size, game_hover_size),
+            //Stretched over the winning line by its transform.
+            Part::Strike => (self.strike_color, 1.0, 0.0),
+            Part::Cell => (self.cell_color, self.cell_size, self.cell_size),
+            Part::CellHover => (self.cell_hover_border_color, highlight_size, self.cell_size),
+            Part::InactiveCellHover => (
+                self.inactive_cell_hover_border_color,
+                highlight_size,
+                self.cell_size,
+            ),
+        };
+        Look {
+            color,
+            optical_size,
+            size,
+        }
+    }
+
+    ///A square of `part`, the mesh and material are created by the first game that needs them.
+    fn square<S: SquareType>(&self, part: Part, assets: &mut GameAssets) -> SquareBuilder<S> {
+        let look = self.look(part);
+        let (mesh, material) = assets
+            .parts
+            .0
+            .entry(part)
+            .or_insert_with(|| {
+                (
+                    assets
+                        .meshes
+                        .add(shape::Quad::new(Vec2::splat(look.optical_size)).into()),
+                    assets.materials.add(ColorMaterial::from(look.color)),
+                )
+            })
+            .clone();
+        SquareBuilder::default()
+            .mesh(mesh)
+            .material(material)
+            .size(look.size)
+    }
+
+    ///Center of the cell at `x`, `y` counted from the bottom left, relative to the game.
+    pub fn cell_position(&self, x: i16, y: i16) -> Vec2 {
+        let cell_width = self.cell_size + self.cell_gap;
+        let game_size = cell_width * self.n as f32 - self.cell_gap;
+        let cell_offset = -(game_size + self.cell_size + self.cell_gap * 2.0) / 4.0;
+        Vec2::new(
+            x as f32 * cell_width + cell_offset,
+            y as f32 * cell_width + cell_offset,
+        )
+    }
+
+    ///Spawns the game, also used to spawn the games of new time steps.
+    pub fn init(&self, commands: &mut Commands, assets: &mut GameAssets) {
         let grid_origin = (self.n as i16 - 1) / 2;
 
         let game = commands
             .spawn((
-                SquareBuilder::new(&mut meshes, &mut materials)
-                    .optical_size(game_highlight_size)
-                    .color(self.game_active_border_color)
-                    .visibility(if active_game.0 == self.game_id {
-                        Visibility::Visible
-                    } else {
-                        Visibility::Hidden
-                    })
-                    .size(game_highlight_size)
+                self.square(Part::GameActive, assets)
+                    .visibility(Visibility::Hidden)
                     .square_type(GameActive)
                     .position(self.origin)
                     .build(),
@@ -162,81 +259,87 @@ impl TicTacToePlugin {
             ))
             .with_children(|game| {
                 game.spawn(
-                    SquareBuilder::new(&mut meshes, &mut materials)
-                        .optical_size(game_hover_size)
-                        .color(self.hover_background_color)
+                    self.square(Part::GameHover, assets)
                         .visibility(Visibility::Hidden)
                         .z_index(2.0)
-                        .size(game_hover_size)
                         .square_type(Hover)
                         .build(),
                 );
                 game.spawn(InactiveHoverBundle {
-                    square_bundle: SquareBuilder::new(&mut meshes, &mut materials)
-                        .optical_size(game_hover_size)
-                        .color(self.inactive_hover_background_color)
+                    square_bundle: self
+                        .square(Part::InactiveGameHover, assets)
                         .visibility(Visibility::Hidden)
                         .z_index(2.0)
-                        .size(game_hover_size)
                         .square_type(Hover)
                         .build(),
                     ..default()
                 });
                 game.spawn(
-                    SquareBuilder::new(&mut meshes, &mut materials)
-                        .optical_size(game_hover_size)
-                        .color(self.background_color)
+                    self.square(Part::Background, assets)
                         .z_index(1.0)
-                        .size(game_hover_size)
                         .square_type(Square)
                         .build(),
                 );
+                game.spawn(
+                    self.square(Part::Strike, assets)
+                        .visibility(Visibility::Hidden)
+                        .z_index(6.0)
+                        .square_type(Strike)
+                        .build(),
+                );
             })
             .id();
-        for x in 0..self.n {
-            for y in 0..self.n {
-                let position = Vec2::new(
-                    x as f32 * cell_width + cell_offset,
-                    y as f32 * cell_width + cell_offset,
-                );
+        for x in 0..self.n as i16 {
+            for y in 0..self.n as i16 {
                 let grid_position =
-                    GridPosition::new(x as i16 - grid_origin, y as i16 - grid_origin, self.game_id);
+                    GridPosition::new(x - grid_origin, y - grid_origin, self.game_id);
 
                 let cell = commands
                     .spawn(CellBundle {
-                        square_bundle: SquareBuilder::new(&mut meshes, &mut materials)
-                            .optical_size(self.cell_size)
-                            .color(self.cell_color)
-                            .size(self.cell_size)
-                            .position(position)
+                        square_bundle: self
+                            .square(Part::Cell, assets)
+                            .position(self.cell_position(x, y))
                             .square_type(Cell)
                             .z_index(4.0)
                             .build(),
-                        grid_position: grid_position.clone(),
+                        grid_position,
                         ..default()
                     })
                     .with_children(|parent| {
                         parent.spawn(
-                            SquareBuilder::new(&mut meshes, &mut materials)
-                                .optical_size(highlight_size)
-                                .color(self.cell_hover_border_color)
+                            self.square(Part::CellHover, assets)
                                 .visibility(Visibility::Hidden)
                                 .z_index(-1.0)
-                                .size(self.cell_size)
                                 .square_type(Hover)
                                 .build(),
                         );
                         parent.spawn(InactiveHoverBundle {
-                            square_bundle: SquareBuilder::new(&mut meshes, &mut materials)
-                                .optical_size(highlight_size)
-                                .color(self.inactive_cell_hover_border_color)
+                            square_bundle: self
+                                .square(Part::InactiveCellHover, assets)
                                 .visibility(Visibility::Hidden)
                                 .z_index(-1.0)
-                                .size(self.cell_size)
                                 .square_type(Hover)
                                 .build(),
                             ..default()
                         });
+                        parent.spawn((
+                            Text2dBundle {
+                                text: Text::from_section(
+                                    "",
+                                    TextStyle {
+                                        font_size: self.cell_size * 0.8,
+                                        ..default()
+                                    },
+                                )
+                                .with_alignment(TextAlignment::Center),
+                                transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                                ..default()
+                            },
+                            Glyph {
+                                x_color: self.x_color,
+                                o_color: self.o_color,
+                            },
+                        ));
                     })
                     .id();
 
@@ -244,19 +347,83 @@ impl TicTacToePlugin {
             }
         }
     }
+
+    ///Applies the colours and sizes to `game`, a game spawned by [TicTacToePlugin::init],
+    /// without respawning it.
+    pub fn restyle(&self, game: Entity, style: &mut GameStyle) {
+        let grid_origin = (self.n as i16 - 1) / 2;
+        let entities: Vec<Entity> = std::iter::once(game)
+            .chain(style.q_children.iter_descendants(game))
+            .collect();
+        for entity in entities {
+            let parent = style.q_parents.get(entity).map(|parent| parent.get());
+            if let Ok((mut glyph, mut text)) = style.q_glyphs.get_mut(entity) {
+                glyph.x_color = self.x_color;
+                glyph.o_color = self.o_color;
+                text.sections[0].style.font_size = self.cell_size * 0.8;
+                //Colours the glyph again.
+                if let Some(mut mark) = parent.ok().and_then(|p| style.q_marks.get_mut(p).ok()) {
+                    mark.set_changed();
+                }
+                continue;
+            }
+            let in_cell = parent.is_ok_and(|parent| {
+                style
+                    .q_squares
+                    .get(parent)
+                    .is_ok_and(|(.., cell, _, _, _)| cell)
+            });
+            let Ok((mut transform, mut size, material, mesh, pos, cell, hover, inactive, strike)) =
+                style.q_squares.get_mut(entity)
+            else {
+                continue;
+            };
+            let part = match (cell, hover, inactive, strike) {
+                _ if entity == game => Part::GameActive,
+                (true, ..) => Part::Cell,
+                (_, true, false, _) if in_cell => Part::CellHover,
+                (_, true, true, _) if in_cell => Part::InactiveCellHover,
+                (_, true, false, _) => Part::GameHover,
+                (_, true, true, _) => Part::InactiveGameHover,
+                (.., true) => Part::Strike,
+                _ => Part::Background,
+            };
+            let look = self.look(part);
+            if let Some(material) = style.materials.get_mut(material) {
+                material.color = look.color;
+            }
+            //The strike is resized by `draw_strikes`.
+            if part == Part::Strike {
+                continue;
+            }
+            if let Some(mesh) = style.meshes.get_mut(&mesh.0) {
+                *mesh = shape::Quad::new(Vec2::splat(look.optical_size)).into();
+            }
+            size.0 = look.size;
+            let z = transform.translation.z;
+            match (part, pos) {
+                (Part::GameActive, _) => transform.translation = self.origin.extend(z),
+                (Part::Cell, Some(pos)) => {
+                    transform.translation = self
+                        .cell_position(pos.x + grid_origin, pos.y + grid_origin)
+                        .extend(z)
+                }
+                _ => {}
+            }
+        }
+    }
 }
 impl Plugin for TicTacToePlugin {
     fn build(&self, app: &mut App) {
         let builder = self.clone();
-        app.init_resource::<ActiveGame>().add_systems(
-            Startup,
-            move |active_game: Res<ActiveGame>,
-                  commands: Commands,
-                  meshes: ResMut<Assets<Mesh>>,
-                  materials: ResMut<Assets<ColorMaterial>>| {
-                builder.init(active_game, commands, meshes, materials);
-            },
-        );
+        app.init_resource::<ActiveGames>()
+            .init_resource::<PartHandles>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, mut assets: GameAssets| {
+                    builder.init(&mut commands, &mut assets);
+                },
+            );
     }
 
     fn is_unique(&self) -> bool {
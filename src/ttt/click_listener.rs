@@ -1,97 +1,256 @@
 use bevy::prelude::*;
-use stttwmdtt::ActiveGame;
+use stttwmdtt::{
+    Action, ActiveGames, BoardDrawn, BoardWon, CurrentPlayer, GameState, MatchOver, PlayerKind,
+    Seats, TurnObligations,
+};
 
 use crate::{
     active_game_listener::{ActivateGame, DeactivateGame},
-    ttt::GameId,
+    ttt::{GameId, GameSystems},
+};
+
+use super::{
+    mark::{draw_marks, draw_strikes, sync_marks},
+    mouse_listener::HoveredPosition,
 };
 
-use super::mouse_listener::HoveredPosition;
+///Whether the turn passes on its own once the current player has no obligations left.
+#[derive(Resource, Clone, Copy)]
+struct AutoSubmit(bool);
+
+///Clicks play moves unless a replay is watched.
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PlayMode {
+    #[default]
+    Play,
+    Replay,
+}
+
+///Names the player to move and the games they still have to play in.
+#[derive(Component)]
+struct TurnText;
+
+///Takes back the last move.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Undo;
+
+///Plays the last undone move again.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Redo;
 
 fn handle_click(
-    games_per_row: u32,
-    game_rows: u32,
-    mut active_game: ResMut<ActiveGame>,
+    mut state: ResMut<GameState>,
     cursor: Res<HoveredPosition>,
     clicks: Res<Input<MouseButton>>,
-    mut activate: EventWriter<ActivateGame>,
-    mut deactivate: EventWriter<DeactivateGame>,
+    seats: Res<Seats>,
+    mut board_won: EventWriter<BoardWon>,
+    mut board_drawn: EventWriter<BoardDrawn>,
+    mut match_over: EventWriter<MatchOver>,
 ) {
-    if cursor
-        .game_id
-        .as_ref()
-        .and_then(|id| {
-            if id != active_game.as_ref() {
-                None
-            } else {
-                Some(true)
-            }
-        })
-        .is_none()
-        || cursor.grid_pos.is_none()
+    if !clicks.just_pressed(MouseButton::Left)
+        || seats.get(state.current_player()) != PlayerKind::Human
     {
         return;
     }
-    if clicks.just_pressed(MouseButton::Left) {
-        let pos = cursor.grid_pos.as_ref().unwrap();
-
-        let active = active_game.0;
-        let active_x = (active / game_rows as u64) as i128;
-        let active_y = (active % game_rows as u64) as i128;
-        let new_x = active_x + pos.x.signum() as i128;
-        let new_x = ((new_x + games_per_row as i128) % games_per_row as i128) as u64;
-        let new_y = active_y + pos.y.signum() as i128;
-        let new_y = ((new_y + game_rows as i128) % game_rows as i128) as u64;
-        let active = game_rows as u64 * new_x + new_y;
-        println!("activeCoords: {} {}", active_x, active_y);
-        println!("newCoords: {} {}", new_x, new_y);
-        println!("Pressed: {}", pos);
-        println!("new_active: {}", active);
-        if active != active_game.0 {
-            deactivate.send(GameId(active_game.0).into());
-            activate.send(GameId(active as u64).into());
-            active_game.0 = active;
+    let Some(pos) = cursor.grid_pos.as_ref() else {
+        return;
+    };
+    if let Ok(outcome) = state.apply_move(*pos) {
+        info!("{}: {}", outcome.player, Action::Move(outcome.position));
+        if let Some(won) = outcome.board_won {
+            board_won.send(won);
         }
+        if let Some(drawn) = outcome.board_drawn {
+            board_drawn.send(drawn);
+        }
+        if let Some(over) = outcome.match_over {
+            match_over.send(over);
+        }
+    }
+}
+
+fn handle_submit(
+    mut state: ResMut<GameState>,
+    keys: Res<Input<KeyCode>>,
+    auto_submit: Res<AutoSubmit>,
+) {
+    let requested = keys.just_pressed(KeyCode::Return);
+    if requested || auto_submit.0 && state.is_changed() && state.can_submit() {
+        if let Ok(player) = state.submit_turn() {
+            info!("{}: {}, {} to move", player.other(), Action::Submit, player);
+        }
+    }
+}
+
+fn handle_history_keys(
+    keys: Res<Input<KeyCode>>,
+    mut undo: EventWriter<Undo>,
+    mut redo: EventWriter<Redo>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Z) {
+        undo.send(Undo);
+    }
+    if keys.just_pressed(KeyCode::Y) {
+        redo.send(Redo);
+    }
+}
+
+fn handle_history(
+    mut state: ResMut<GameState>,
+    mut undo: EventReader<Undo>,
+    mut redo: EventReader<Redo>,
+    auto_submit: Res<AutoSubmit>,
+    seats: Res<Seats>,
+) {
+    let human_plays = seats.x == PlayerKind::Human || seats.o == PlayerKind::Human;
+    for _ in undo.read() {
+        state.undo_turn(auto_submit.0, |player| {
+            human_plays && seats.get(player) != PlayerKind::Human
+        });
+    }
+    for _ in redo.read() {
+        let _ = state.redo();
+    }
+}
+
+///Keeps the turn resources and the active games in line with the [GameState].
+fn sync_turn(
+    state: Res<GameState>,
+    mut active_games: ResMut<ActiveGames>,
+    mut current_player: ResMut<CurrentPlayer>,
+    mut obligations: ResMut<TurnObligations>,
+    mut activate: EventWriter<ActivateGame>,
+    mut deactivate: EventWriter<DeactivateGame>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let next_games: ActiveGames = state.reachable_games().into_iter().collect();
+    for id in active_games.0.difference(&next_games.0) {
+        deactivate.send(GameId(*id).into());
+    }
+    for id in next_games.0.difference(&active_games.0) {
+        activate.send(GameId(*id).into());
+    }
+    *active_games = next_games;
+    current_player.0 = state.current_player();
+    obligations.0 = state.obligations();
+}
+
+fn init_turn_text(mut commands: Commands) {
+    commands.spawn((
+        TurnText,
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn draw_turn_text(
+    current_player: Res<CurrentPlayer>,
+    obligations: Res<TurnObligations>,
+    auto_submit: Res<AutoSubmit>,
+    mut q_text: Query<&mut Text, With<TurnText>>,
+) {
+    if !current_player.is_changed() && !obligations.is_changed() {
+        return;
+    }
+    let games: Vec<String> = obligations.0.iter().map(ToString::to_string).collect();
+    let value = if !games.is_empty() {
+        format!(
+            "{} to move, play in {} to pass the turn",
+            current_player.0,
+            games.join(", ")
+        )
+    } else if auto_submit.0 {
+        format!("{} to move", current_player.0)
+    } else {
+        format!("{} to move, Return passes the turn", current_player.0)
+    };
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = value.clone();
     }
 }
 
 pub struct ClickListener {
-    games_per_row: u32,
-    game_rows: u32,
+    state: GameState,
+    auto_submit: bool,
 }
 impl ClickListener {
-    pub fn new(games_per_row: u32, game_rows: u32) -> Self {
+    ///Plays on from `state`.
+    pub fn new(state: GameState) -> Self {
         Self {
-            games_per_row,
-            game_rows,
+            state,
+            auto_submit: true,
         }
     }
+
+    ///Without auto submit every turn has to be passed with the return key.
+    pub fn auto_submit(mut self, value: bool) -> Self {
+        self.auto_submit = value;
+        self
+    }
 }
 impl Plugin for ClickListener {
     fn build(&self, app: &mut App) {
-        let games_per_row = self.games_per_row.clone();
-        let game_rows = self.game_rows.clone();
-        app.init_resource::<HoveredPosition>()
-            .init_resource::<ActiveGame>()
+        app.insert_resource(self.state.clone())
+            .insert_resource(AutoSubmit(self.auto_submit))
+            .init_resource::<Seats>()
+            .init_resource::<HoveredPosition>()
+            .init_resource::<ActiveGames>()
+            .init_resource::<CurrentPlayer>()
+            .init_resource::<TurnObligations>()
             .add_event::<ActivateGame>()
             .add_event::<DeactivateGame>()
+            .add_event::<BoardWon>()
+            .add_event::<BoardDrawn>()
+            .add_event::<MatchOver>()
+            .add_event::<Undo>()
+            .add_event::<Redo>()
+            .add_state::<PlayMode>()
+            .add_systems(Startup, init_turn_text)
+            .configure_sets(
+                Update,
+                (GameSystems::Input, GameSystems::Spawn, GameSystems::View).chain(),
+            )
             .add_systems(
                 Update,
-                move |active_game: ResMut<ActiveGame>,
-                      cursor: Res<HoveredPosition>,
-                      clicks: Res<Input<MouseButton>>,
-                      activate: EventWriter<ActivateGame>,
-                      deactivate: EventWriter<DeactivateGame>| {
-                    handle_click(
-                        games_per_row,
-                        game_rows,
-                        active_game,
-                        cursor,
-                        clicks,
-                        activate,
-                        deactivate,
+                (
+                    (
+                        handle_click,
+                        handle_history_keys,
+                        handle_history,
+                        handle_submit,
                     )
-                },
+                        .chain()
+                        .run_if(in_state(PlayMode::Play)),
+                    sync_turn,
+                )
+                    .chain()
+                    .in_set(GameSystems::Input),
+            )
+            .add_systems(
+                Update,
+                (sync_marks, draw_marks, draw_strikes, draw_turn_text)
+                    .chain()
+                    .in_set(GameSystems::View),
             );
     }
 }
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use stttwmdtt::{GameState, GridPosition, Player};
+
+use super::{
+    square::{SquareSize, Strike},
+    GameId,
+};
+
+#[derive(Component, PartialEq, Clone, Default, Debug)]
+pub struct Mark(pub Option<Player>);
+
+///Text child of a cell showing its [Mark].
+#[derive(Component, Clone)]
+pub struct Glyph {
+    pub x_color: Color,
+    pub o_color: Color,
+}
+
+pub fn sync_marks(state: Res<GameState>, mut q_cells: Query<(&GridPosition, &mut Mark)>) {
+    for (pos, mut mark) in q_cells.iter_mut() {
+        if !state.is_changed() && !mark.is_added() {
+            continue;
+        }
+        let player = state.board(pos.board()).and_then(|b| b.get(pos.x, pos.y));
+        if mark.0 != player {
+            mark.0 = player;
+        }
+    }
+}
+
+pub fn draw_marks(
+    q_cells: Query<(&Mark, &Children), Changed<Mark>>,
+    mut q_glyphs: Query<(&mut Text, &Glyph)>,
+) {
+    for (mark, children) in q_cells.iter() {
+        for child in children.iter() {
+            if let Ok((mut text, glyph)) = q_glyphs.get_mut(*child) {
+                let section = &mut text.sections[0];
+                match mark.0 {
+                    Some(player) => {
+                        section.value = player.to_string();
+                        section.style.color = match player {
+                            Player::X => glyph.x_color,
+                            Player::O => glyph.o_color,
+                        };
+                    }
+                    None => section.value.clear(),
+                }
+            }
+        }
+    }
+}
+
+type SpawnedOrResized = Or<(Added<GameId>, Changed<SquareSize>)>;
+
+///Stretches the [Strike] of a newly spawned or resized won game over its winning line.
+pub fn draw_strikes(
+    state: Res<GameState>,
+    q_games: Query<(&GameId, &Children), SpawnedOrResized>,
+    q_cells: Query<(&GridPosition, &Transform, &SquareSize), Without<Strike>>,
+    mut q_strikes: Query<(&mut Transform, &mut Visibility), With<Strike>>,
+) {
+    for (id, children) in q_games.iter() {
+        let Some(line) = state.board(id.0).map(|board| board.winning_line()) else {
+            continue;
+        };
+        let (Some(first), Some(last)) = (line.first(), line.last()) else {
+            continue;
+        };
+        let cell = |(x, y): &(i16, i16)| {
+            children
+                .iter()
+                .filter_map(|child| q_cells.get(*child).ok())
+                .find(|(pos, _, _)| pos.x == *x && pos.y == *y)
+                .map(|(_, transform, size)| (transform.translation.truncate(), size.0))
+        };
+        let (Some((start, cell_size)), Some((end, _))) = (cell(first), cell(last)) else {
+            continue;
+        };
+        let line = end - start;
+        for child in children.iter() {
+            if let Ok((mut transform, mut visibility)) = q_strikes.get_mut(*child) {
+                transform.translation = ((start + end) / 2.0).extend(transform.translation.z);
+                transform.rotation = Quat::from_rotation_z(line.y.atan2(line.x));
+                transform.scale = Vec3::new(line.length() + cell_size * 0.8, cell_size / 10.0, 1.0);
+                *visibility = Visibility::Visible;
+            }
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use super::{
     square::{Cell, Hover, SquareSize},
-    GameId, GridPosition, Inactive,
+    GameId, GameSystems, GridPosition, Inactive,
 };
 use bevy::prelude::*;
-use stttwmdtt::{ActiveGame, CursorPosition};
+use stttwmdtt::{ActiveGames, CursorPosition};
 use stttwmdtt_derive::WrapperEvent;
 
 pub trait WrapperEvent<T: Clone + PartialEq>: Event + From<T> {
@@ -55,7 +55,7 @@ macro_rules! inner_hover_listener {
         mod $fn_name {
             use super::*;
             pub fn active(
-                active_game: Res<ActiveGame>,
+                active_games: Res<ActiveGames>,
                 mut event_reader: EventReader<$event_type>,
                 query: $query,
                 mut q_hovers: Query<&mut Visibility, (With<Hover>, Without<Inactive>)>,
@@ -63,7 +63,7 @@ macro_rules! inner_hover_listener {
                 for event in event_reader.read() {
                     let check_value = &event.0;
                     if Visibility::$visibility == Visibility::Visible
-                        && check_value != active_game.as_ref()
+                        && check_value != active_games.as_ref()
                     {
                         continue;
                     }
@@ -81,14 +81,14 @@ macro_rules! inner_hover_listener {
                 }
             }
             pub fn inactive(
-                active_game: Res<ActiveGame>,
+                active_games: Res<ActiveGames>,
                 mut event_reader: EventReader<$event_type>,
                 query: $query,
                 mut q_inactive_hovers: Query<&mut Visibility, (With<Hover>, With<Inactive>)>,
             ) {
                 for event in event_reader.read() {
                     let check_value = &event.0;
-                    if check_value == active_game.as_ref() {
+                    if check_value == active_games.as_ref() {
                         continue;
                     }
                     println!("inactive {}: {:?}", stringify!($fn_name), check_value);
@@ -149,31 +149,43 @@ fn mouse_listener_hover(
     cell_exited: EventWriter<MouseExitedCell>,
     game_entered: EventWriter<MouseEnteredGame>,
     game_exited: EventWriter<MouseExitedGame>,
-    q_hover_positions: Query<(&Parent, &GlobalTransform, &SquareSize), With<Hover>>,
-    q_grid_pos: Query<&GridPosition, With<Cell>>,
-    q_games: Query<&GameId>,
+    q_games: Query<(&GameId, &GlobalTransform, &SquareSize, &Children)>,
+    q_cells: Query<(&GridPosition, &Children), With<Cell>>,
+    q_hovers: Query<(&GlobalTransform, &SquareSize), With<Hover>>,
 ) {
-    let mut new_hovered_pos = None;
-    let mut new_hovered_id = None;
-    for (parent, transform, size) in &q_hover_positions {
+    let under_cursor = |transform: &GlobalTransform, size: &SquareSize| {
         let half_size = size.0 / 2.0;
         let x_max = transform.transform_point(Vec3::X * half_size).x;
         let x_min: f32 = transform.transform_point(Vec3::NEG_X * half_size).x;
         let y_max = transform.transform_point(Vec3::Y * half_size).y;
         let y_min = transform.transform_point(Vec3::NEG_Y * half_size).y;
         let cursor_pos = cursor.0.extend(0.0);
-        if cursor_pos.x >= x_min
+        cursor_pos.x >= x_min
             && cursor_pos.x <= x_max
             && cursor_pos.y >= y_min
             && cursor_pos.y <= y_max
-        {
-            if let Ok(grid_pos) = q_grid_pos.get(parent.get()) {
-                new_hovered_pos = Some(grid_pos.clone());
-                new_hovered_id = Some(GameId(grid_pos.id.clone()));
-                break;
-            } else if let Ok(game_id) = q_games.get(parent.get()) {
-                new_hovered_id = Some(game_id.clone());
-            }
+    };
+    let hovers_under_cursor = |children: &Children| {
+        children.iter().any(|child| {
+            q_hovers
+                .get(*child)
+                .is_ok_and(|(transform, size)| under_cursor(transform, size))
+        })
+    };
+    let mut new_hovered_pos = None;
+    let mut new_hovered_id = None;
+    //Games do not overlap, so only the hovers of the game under the cursor are checked.
+    let game = q_games
+        .iter()
+        .find(|(_, transform, size, _)| under_cursor(transform, size));
+    if let Some((game_id, _, _, children)) = game {
+        new_hovered_pos = children
+            .iter()
+            .filter_map(|child| q_cells.get(*child).ok())
+            .find(|(_, cell_children)| hovers_under_cursor(cell_children))
+            .map(|(grid_pos, _)| *grid_pos);
+        if new_hovered_pos.is_some() || hovers_under_cursor(children) {
+            new_hovered_id = Some(game_id.clone());
         }
     }
 
@@ -189,10 +201,43 @@ fn mouse_listener_hover(
     hovered.grid_pos = new_hovered_pos;
 }
 
+///Swaps the hovers of the hovered cell and game when they turn active or inactive beneath the
+/// cursor, e.g. after an undo.
+fn refresh_hover(
+    active_games: Res<ActiveGames>,
+    hovered: Res<HoveredPosition>,
+    q_cells: Query<(&GridPosition, &Children), With<Cell>>,
+    q_games: Query<(&GameId, &Children)>,
+    mut q_hovers: Query<(&mut Visibility, Option<&Inactive>), With<Hover>>,
+) {
+    if !active_games.is_changed() {
+        return;
+    }
+    let hovered_cell = q_cells
+        .iter()
+        .find(|(pos, _)| Some(**pos) == hovered.grid_pos)
+        .map(|(pos, children)| (*pos == *active_games, children));
+    let hovered_game = q_games
+        .iter()
+        .find(|(id, _)| hovered.game_id.as_ref() == Some(*id))
+        .map(|(id, children)| (*id == *active_games, children));
+    for (active, children) in hovered_cell.into_iter().chain(hovered_game) {
+        for child in children.iter().take(2) {
+            if let Ok((mut visibility, inactive)) = q_hovers.get_mut(*child) {
+                *visibility = if active != inactive.is_some() {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                };
+            }
+        }
+    }
+}
+
 pub struct MouseListenerPlugin;
 impl Plugin for MouseListenerPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ActiveGame>()
+        app.init_resource::<ActiveGames>()
             .init_resource::<CursorPosition>()
             .init_resource::<HoveredPosition>()
             .add_event::<MouseEnteredCell>()
@@ -202,20 +247,25 @@ impl Plugin for MouseListenerPlugin {
             .add_systems(
                 Update,
                 (
-                    (highlight_hover_cell::active, dehighlight_hover_cell::active).chain(),
-                    (
-                        highlight_hover_cell::inactive,
-                        dehighlight_hover_cell::inactive,
-                    )
-                        .chain(),
-                    (highlight_hover_game::active, dehighlight_hover_game::active).chain(),
+                    mouse_listener_hover,
                     (
-                        highlight_hover_game::inactive,
-                        dehighlight_hover_game::inactive,
-                    )
-                        .chain(),
-                ),
-            )
-            .add_systems(Update, mouse_listener_hover);
+                        (highlight_hover_cell::active, dehighlight_hover_cell::active).chain(),
+                        (
+                            highlight_hover_cell::inactive,
+                            dehighlight_hover_cell::inactive,
+                        )
+                            .chain(),
+                        (highlight_hover_game::active, dehighlight_hover_game::active).chain(),
+                        (
+                            highlight_hover_game::inactive,
+                            dehighlight_hover_game::inactive,
+                        )
+                            .chain(),
+                    ),
+                    refresh_hover,
+                )
+                    .chain()
+                    .after(GameSystems::View),
+            );
     }
 }
@@ -18,6 +18,8 @@ pub struct Hover;
 pub struct GameActive;
 #[derive(SquareType)]
 pub struct Square;
+#[derive(SquareType)]
+pub struct Strike;
 
 #[derive(Bundle)]
 pub struct SquareBundle<M: Material2d, S: SquareType> {
@@ -35,34 +37,23 @@ impl<M: Material2d, S: SquareType> Default for SquareBundle<M, S> {
     }
 }
 
+///A square drawn with `mesh` and `material`, which are shared with other squares.
 #[derive(Builder)]
-pub struct SquareBuilder<'a, S: SquareType> {
-    color: Color,
+pub struct SquareBuilder<S: SquareType> {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
     size: f32,
-    optical_size: f32,
     square_type: S,
     z_index: f32,
     visibility: Visibility,
     position: Vec2,
-    meshes: Option<&'a mut Assets<Mesh>>,
-    materials: Option<&'a mut Assets<ColorMaterial>>,
 }
-impl<S: SquareType> From<SquareBuilder<'_, S>> for SquareBundle<ColorMaterial, S> {
+impl<S: SquareType> From<SquareBuilder<S>> for SquareBundle<ColorMaterial, S> {
     fn from(value: SquareBuilder<S>) -> Self {
-        if value.meshes.is_none() || value.materials.is_none() {
-            panic!("Someone forgot the materials and/or meshes on the SquareBuilder");
-        }
         Self {
             mesh: MaterialMesh2dBundle {
-                mesh: value
-                    .meshes
-                    .unwrap()
-                    .add(shape::Quad::new(Vec2::splat(value.optical_size)).into())
-                    .into(),
-                material: value
-                    .materials
-                    .unwrap()
-                    .add(ColorMaterial::from(value.color)),
+                mesh: value.mesh.into(),
+                material: value.material,
                 visibility: value.visibility,
                 transform: Transform::from_translation(value.position.extend(value.z_index)),
                 ..default()
@@ -72,30 +63,20 @@ impl<S: SquareType> From<SquareBuilder<'_, S>> for SquareBundle<ColorMaterial, S
         }
     }
 }
-impl<S: SquareType> Default for SquareBuilder<'_, S> {
+impl<S: SquareType> Default for SquareBuilder<S> {
     fn default() -> Self {
         Self {
-            color: default(),
+            mesh: default(),
+            material: default(),
             size: default(),
-            optical_size: default(),
             square_type: default(),
             z_index: 0.0,
             visibility: Visibility::Visible,
             position: Vec2::splat(0.0),
-            meshes: default(),
-            materials: default(),
         }
     }
 }
-impl<'a, S: SquareType> SquareBuilder<'a, S> {
-    pub fn new(meshes: &'a mut Assets<Mesh>, materials: &'a mut Assets<ColorMaterial>) -> Self {
-        Self {
-            meshes: Some(meshes),
-            materials: Some(materials),
-            ..default()
-        }
-    }
-
+impl<S: SquareType> SquareBuilder<S> {
     pub fn build(self) -> SquareBundle<ColorMaterial, S> {
         self.into()
     }
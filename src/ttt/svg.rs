@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use stttwmdtt::{Board, Player};
+
+use super::{Part, TicTacToePlugin};
+
+///`fill` and `fill-opacity` attributes of `color`.
+fn fill(color: Color) -> String {
+    let [red, green, blue, _] = color.as_rgba_u8();
+    format!(
+        "fill=\"#{:02x}{:02x}{:02x}\" fill-opacity=\"{}\"",
+        red,
+        green,
+        blue,
+        color.a()
+    )
+}
+
+///A rectangle around `center`, in world coordinates.
+pub fn svg_rect(center: Vec2, size: Vec2, color: Color) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>\n",
+        center.x - size.x / 2.0,
+        center.y - size.y / 2.0,
+        size.x,
+        size.y,
+        fill(color)
+    )
+}
+
+impl TicTacToePlugin {
+    fn svg_part(&self, part: Part, center: Vec2) -> String {
+        let look = self.look(part);
+        svg_rect(center, Vec2::splat(look.optical_size), look.color)
+    }
+
+    ///The game as [TicTacToePlugin::init] spawns it, in world coordinates with y pointing up.
+    pub fn svg(&self, board: Option<&Board>, active: bool) -> String {
+        let grid_origin = (self.n as i16 - 1) / 2;
+        let mut svg = String::new();
+        if active {
+            svg += &self.svg_part(Part::GameActive, self.origin);
+        }
+        svg += &self.svg_part(Part::Background, self.origin);
+        let center =
+            |x: i16, y: i16| self.origin + self.cell_position(x + grid_origin, y + grid_origin);
+        for x in 0..self.n as i16 {
+            for y in 0..self.n as i16 {
+                svg += &self.svg_part(Part::Cell, self.origin + self.cell_position(x, y));
+            }
+        }
+        let Some(board) = board else {
+            return svg;
+        };
+        for x in -grid_origin..self.n as i16 - grid_origin {
+            for y in -grid_origin..self.n as i16 - grid_origin {
+                let Some(player) = board.get(x, y) else {
+                    continue;
+                };
+                let color = match player {
+                    Player::X => self.x_color,
+                    Player::O => self.o_color,
+                };
+                //Flipped back, the image is drawn with y pointing up.
+                let position = center(x, y);
+                svg += &format!(
+                    "<text x=\"{}\" y=\"{}\" transform=\"scale(1 -1)\" font-size=\"{}\" \
+                     font-family=\"sans-serif\" text-anchor=\"middle\" \
+                     dominant-baseline=\"central\" {}>{}</text>\n",
+                    position.x,
+                    -position.y,
+                    self.cell_size * 0.8,
+                    fill(color),
+                    player
+                );
+            }
+        }
+        //Stretched like `draw_strikes` does.
+        let line = board.winning_line();
+        if let (Some(first), Some(last)) = (line.first(), line.last()) {
+            let (start, end) = (center(first.0, first.1), center(last.0, last.1));
+            let direction = (end - start).try_normalize().unwrap_or(Vec2::X);
+            let (start, end) = (
+                start - direction * self.cell_size * 0.4,
+                end + direction * self.cell_size * 0.4,
+            );
+            let [red, green, blue, _] = self.strike_color.as_rgba_u8();
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#{:02x}{:02x}{:02x}\" \
+                 stroke-opacity=\"{}\" stroke-width=\"{}\"/>\n",
+                start.x,
+                start.y,
+                end.x,
+                end.y,
+                red,
+                green,
+                blue,
+                self.strike_color.a(),
+                self.cell_size / 10.0
+            );
+        }
+        svg
+    }
+}